@@ -0,0 +1,8 @@
+use crate::domain::alert::Alert;
+use anyhow::Result;
+
+// Somewhere alerts are delivered to. Sinks run on a thread of their own, so
+// they may block.
+pub trait AlertSink: Send {
+    fn send(&mut self, alert: &Alert) -> Result<()>;
+}
@@ -1,18 +1,51 @@
 use crate::domain::block::Block;
+use crate::domain::commitment::FinalityUpdate;
+use crate::domain::connection::ConnectionState;
+use crate::domain::slot_tracker::MissedSlot;
+use crate::domain::transaction_detail::TransactionDetail;
 use anyhow::Result;
 use futures_util::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use tokio::sync::mpsc::UnboundedSender;
 
+pub type Unsubscribe = Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>;
+
+// What a subscription delivers: the blocks, the slots whose block could not
+// be fetched, how the connection is doing and, below finalized commitment,
+// what became of the blocks already delivered.
+#[derive(Debug)]
+pub enum BlockUpdate {
+    Block(Block),
+    Missed(MissedSlot),
+    Connection(ConnectionState),
+    Finality(FinalityUpdate),
+}
+
+#[allow(async_fn_in_trait)]
 pub trait BlockGateway {
+    // Sends the block of every completed slot. With a start slot, the blocks
+    // from there up to the tip are sent first, so nothing is missed in between.
+    // The ready sender gets whether the first subscription started.
     async fn subscribe(
         self: Arc<Self>,
-        ready_sender: &UnboundedSender<()>,
-        unsubscribe_sender: &UnboundedSender<
-            Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>,
-        >,
-        block_update_sender: &UnboundedSender<Block>,
+        start_slot: Option<u64>,
+        ready_sender: &UnboundedSender<Result<(), String>>,
+        unsubscribe_sender: &UnboundedSender<Unsubscribe>,
+        block_update_sender: &UnboundedSender<BlockUpdate>,
     ) -> Result<()>;
     fn get_block(&self, block: u64) -> Result<Block, String>;
+    fn get_transaction(&self, signature: &str) -> Result<TransactionDetail, String>;
+    fn get_slot(&self) -> Result<u64, String>;
+    fn get_first_available_block(&self) -> Result<u64, String>;
+    fn get_blocks(&self, start_slot: u64, end_slot: u64) -> Result<Vec<u64>, String>;
+    fn get_blocks_with_limit(&self, start_slot: u64, limit: usize) -> Result<Vec<u64>, String>;
+    fn get_block_time(&self, slot: u64) -> Result<i64, String>;
+    fn get_signatures_for_address(
+        &self,
+        address: &str,
+        before: Option<String>,
+        limit: usize,
+    ) -> Result<Vec<String>, String>;
+    fn get_token_accounts(&self, owner: &str) -> Result<Vec<String>, String>;
 }
@@ -0,0 +1,27 @@
+use crate::domain::block::Block;
+use crate::domain::mint::Mint;
+use crate::domain::report::{HourlyVolume, SenderTotal, StoredTransfer};
+use anyhow::Result;
+
+// Somewhere blocks are kept for later queries. Saving the same block again
+// replaces what was stored for it rather than adding to it.
+pub trait BlockStore {
+    fn save(&mut self, block: &Block) -> Result<()>;
+
+    // Forgets the block of the slot, e.g. one dropped before it was finalized.
+    fn remove(&mut self, slot: u64) -> Result<()>;
+
+    // The `limit` largest senders of each mint, by volume.
+    fn top_senders(&self, token: Option<&Mint>, limit: usize) -> Result<Vec<SenderTotal>>;
+
+    // The volume of each mint over the `limit` latest hours, oldest first.
+    fn volume_by_hour(&self, token: Option<&Mint>, limit: usize) -> Result<Vec<HourlyVolume>>;
+
+    // The `limit` latest transfers sent or received by the address, oldest first.
+    fn history(
+        &self,
+        address: &str,
+        token: Option<&Mint>,
+        limit: usize,
+    ) -> Result<Vec<StoredTransfer>>;
+}
@@ -0,0 +1,8 @@
+use anyhow::Result;
+
+// Remembers the last slot whose block went all the way through the outputs,
+// so that tracking can resume after it.
+pub trait Checkpoint {
+    fn load(&self) -> Result<Option<u64>>;
+    fn save(&mut self, slot: u64) -> Result<()>;
+}
@@ -1 +1,4 @@
+pub mod alert_sink;
 pub mod block_gateway;
+pub mod block_store;
+pub mod checkpoint;
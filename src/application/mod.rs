@@ -1,2 +1,3 @@
 pub mod gateways;
+pub mod presenters;
 pub mod services;
@@ -0,0 +1,131 @@
+use crate::application::gateways::alert_sink::AlertSink;
+use crate::application::presenters::Presenter;
+use crate::domain::alert::{Alert, AlertEngine};
+use crate::domain::block::Block;
+use anyhow::{anyhow, Result};
+use std::sync::mpsc::{channel, Sender};
+use std::thread::JoinHandle;
+use std::time::Instant;
+
+// Evaluates the alert rules on every block and hands the alerts to the sinks.
+// Sinks run on a thread of their own, so a slow webhook or command never holds
+// up the blocks.
+pub struct AlertPresenter {
+    engine: AlertEngine,
+    sender: Option<Sender<Alert>>,
+    dispatcher: Option<JoinHandle<()>>,
+}
+
+impl AlertPresenter {
+    pub fn new(engine: AlertEngine, mut sinks: Vec<Box<dyn AlertSink>>) -> Self {
+        let (sender, receiver) = channel::<Alert>();
+        let dispatcher = std::thread::spawn(move || {
+            for alert in receiver {
+                for sink in sinks.iter_mut() {
+                    if let Err(e) = sink.send(&alert) {
+                        eprintln!("Error sending alert: {}", e);
+                    }
+                }
+            }
+        });
+
+        Self {
+            engine,
+            sender: Some(sender),
+            dispatcher: Some(dispatcher),
+        }
+    }
+}
+
+impl Presenter for AlertPresenter {
+    fn present(&mut self, block: &Block) -> Result<()> {
+        let Some(sender) = &self.sender else {
+            return Ok(());
+        };
+
+        for alert in self
+            .engine
+            .evaluate(block, Instant::now(), chrono::Utc::now().timestamp())
+        {
+            sender
+                .send(alert)
+                .map_err(|_| anyhow!("the alert sinks stopped"))?;
+        }
+
+        Ok(())
+    }
+
+    // Waits for the pending alerts to be delivered.
+    fn finish(&mut self) -> Result<()> {
+        self.sender.take();
+        if let Some(dispatcher) = self.dispatcher.take() {
+            dispatcher
+                .join()
+                .map_err(|_| anyhow!("an alert sink panicked"))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::address_book::AddressBook;
+    use crate::domain::alert::AlertRule;
+    use crate::domain::amount::Amount;
+    use crate::domain::filter::TransferFilter;
+    use crate::domain::mint::Mint;
+    use crate::domain::program::Program;
+    use crate::domain::transaction::Transaction;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    struct MemorySink(Arc<Mutex<Vec<String>>>);
+
+    impl AlertSink for MemorySink {
+        fn send(&mut self, alert: &Alert) -> Result<()> {
+            self.0.lock().unwrap().push(alert.rule.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_alerts_reach_every_sink() {
+        let first = Arc::new(Mutex::new(Vec::new()));
+        let second = Arc::new(Mutex::new(Vec::new()));
+        let engine = AlertEngine::new(
+            vec![AlertRule {
+                name: "any".to_string(),
+                filter: TransferFilter::new(),
+                burst: None,
+            }],
+            Duration::from_secs(60),
+            None,
+            AddressBook::new(),
+        );
+        let mut presenter = AlertPresenter::new(
+            engine,
+            vec![
+                Box::new(MemorySink(Arc::clone(&first))),
+                Box::new(MemorySink(Arc::clone(&second))),
+            ],
+        );
+        let mut block = Block::new(1, "hash".to_string());
+        block.add_transaction(Transaction::new(
+            "sig".to_string(),
+            Account::new("alice", 1, Amount::zero(6)),
+            Account::new("bob", 2, Amount::zero(6)),
+            Program::new("program", 3),
+            "USDC".parse::<Mint>().unwrap(),
+            Amount::new(1, 6),
+        ));
+
+        presenter.present(&block).unwrap();
+        presenter.finish().unwrap();
+
+        assert_eq!(*first.lock().unwrap(), vec!["any".to_string()]);
+        assert_eq!(*second.lock().unwrap(), vec!["any".to_string()]);
+    }
+}
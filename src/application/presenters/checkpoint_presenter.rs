@@ -0,0 +1,22 @@
+use crate::application::gateways::checkpoint::Checkpoint;
+use crate::application::presenters::Presenter;
+use crate::domain::block::Block;
+use anyhow::Result;
+
+// Records the slot of every block. Placed after the other outputs, a block
+// is only checkpointed once they all presented it.
+pub struct CheckpointPresenter<C> {
+    checkpoint: C,
+}
+
+impl<C: Checkpoint> CheckpointPresenter<C> {
+    pub fn new(checkpoint: C) -> Self {
+        Self { checkpoint }
+    }
+}
+
+impl<C: Checkpoint> Presenter for CheckpointPresenter<C> {
+    fn present(&mut self, block: &Block) -> Result<()> {
+        self.checkpoint.save(block.slot)
+    }
+}
@@ -0,0 +1,183 @@
+use crate::application::presenters::{Presenter, SCHEMA_VERSION};
+use crate::domain::address_book::AddressBook;
+use crate::domain::block::Block;
+use crate::domain::commitment::FinalityUpdate;
+use anyhow::Result;
+use std::io::Write;
+
+const HEADER: [&str; 20] = [
+    "schema_version",
+    "slot",
+    "blockhash",
+    "signature",
+    "source",
+    "destination",
+    "mint",
+    "symbol",
+    "amount_raw",
+    "decimals",
+    "amount",
+    "fee_raw",
+    "fee",
+    "program",
+    "program_kind",
+    "source_label",
+    "destination_label",
+    "program_label",
+    "commitment",
+    "finality",
+];
+
+// Writes one row per transaction, the header comes first. Label columns are
+// empty for addresses that are not in the address book. A block presented
+// below finalized gets a row once it settles, with only the slot, blockhash
+// and finality filled in.
+pub struct CsvPresenter<W: Write> {
+    writer: csv::Writer<W>,
+    header_written: bool,
+    address_book: AddressBook,
+}
+
+impl<W: Write> CsvPresenter<W> {
+    pub fn new(writer: W, address_book: AddressBook) -> Self {
+        Self {
+            writer: csv::Writer::from_writer(writer),
+            header_written: false,
+            address_book,
+        }
+    }
+
+    fn label(&self, address: &str) -> String {
+        self.address_book
+            .get(address)
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    fn write_header(&mut self) -> Result<()> {
+        if !self.header_written {
+            self.writer.write_record(HEADER)?;
+            self.header_written = true;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Write> Presenter for CsvPresenter<W> {
+    fn present(&mut self, block: &Block) -> Result<()> {
+        self.write_header()?;
+
+        for transaction in &block.transactions {
+            let amount = transaction.amount();
+            let program_kind = serde_json::to_value(transaction.program.kind)?;
+
+            self.writer.write_record([
+                SCHEMA_VERSION.to_string(),
+                block.slot.to_string(),
+                block.blockhash.clone(),
+                transaction.signature.clone(),
+                transaction.source.address.clone(),
+                transaction.destination.address.clone(),
+                transaction.token.address.clone(),
+                transaction.token.symbol.clone().unwrap_or_default(),
+                amount.raw.to_string(),
+                amount.decimals.to_string(),
+                amount.to_string(),
+                transaction
+                    .fee
+                    .map(|fee| fee.raw.to_string())
+                    .unwrap_or_default(),
+                transaction
+                    .fee
+                    .map(|fee| fee.to_string())
+                    .unwrap_or_default(),
+                transaction.program.address.clone(),
+                program_kind.as_str().unwrap_or_default().to_string(),
+                self.label(&transaction.source.address),
+                self.label(&transaction.destination.address),
+                self.label(&transaction.program.address),
+                block.commitment.to_string(),
+                String::new(),
+            ])?;
+        }
+
+        Ok(self.writer.flush()?)
+    }
+
+    fn finality(&mut self, update: &FinalityUpdate) -> Result<()> {
+        self.write_header()?;
+
+        let finality = serde_json::to_value(update.finality)?;
+        let mut record = vec![String::new(); HEADER.len()];
+        record[0] = SCHEMA_VERSION.to_string();
+        record[1] = update.slot.to_string();
+        record[2] = update.blockhash.clone();
+        record[HEADER.len() - 1] = finality.as_str().unwrap_or_default().to_string();
+        self.writer.write_record(&record)?;
+
+        Ok(self.writer.flush()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::amount::Amount;
+    use crate::domain::commitment::Finality;
+    use crate::domain::mint::Mint;
+    use crate::domain::program::{Program, TOKEN_2022_PROGRAM_ID};
+    use crate::domain::transaction::Transaction;
+
+    #[test]
+    fn test_present_csv() {
+        let mut block = Block::new(7, "hash".to_string());
+        let mut transaction = Transaction::new(
+            "sig".to_string(),
+            Account::new("source, inc", 1, Amount::new(100, 2)),
+            Account::new("destination", 2, Amount::new(0, 2)),
+            Program::new(TOKEN_2022_PROGRAM_ID, 3),
+            Mint::new("mint"),
+            Amount::new(150, 2),
+        );
+        transaction.update_fee(Amount::new(5, 2));
+        block.add_transaction(transaction);
+
+        let mut output: Vec<u8> = Vec::new();
+        let mut presenter = CsvPresenter::new(&mut output, AddressBook::builtin());
+        presenter.present(&block).unwrap();
+        presenter.present(&block).unwrap();
+        drop(presenter);
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("schema_version,slot,"));
+        assert_eq!(
+            lines[1],
+            "1,7,hash,sig,\"source, inc\",destination,mint,,150,2,1.5,5,0.05,TokenzQdBNbLqP5VEhdkAS6EPFLC1PazV3Y6ZKm7k1aM,token_2022,,,Token-2022 Program,finalized,"
+        );
+    }
+
+    #[test]
+    fn test_present_csv_finality() {
+        let mut output: Vec<u8> = Vec::new();
+        let mut presenter = CsvPresenter::new(&mut output, AddressBook::new());
+        presenter
+            .finality(&FinalityUpdate {
+                slot: 7,
+                blockhash: "hash".to_string(),
+                finality: Finality::Dropped,
+            })
+            .unwrap();
+        drop(presenter);
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1], "1,7,hash,,,,,,,,,,,,,,,,,dropped");
+    }
+}
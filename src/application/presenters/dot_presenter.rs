@@ -0,0 +1,169 @@
+use crate::application::presenters::Presenter;
+use crate::domain::address_book::AddressBook;
+use crate::domain::block::Block;
+use crate::domain::transfer_graph::TransferGraph;
+use anyhow::Result;
+use std::io::Write;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GraphOptions {
+    pub merge_parallel: bool,
+    pub dust: f64,
+    pub top: Option<usize>,
+}
+
+// Collects the transfers of every block and writes a single Graphviz digraph
+// once the last block was presented, render it with `dot -Tsvg`.
+pub struct DotPresenter<W> {
+    writer: W,
+    options: GraphOptions,
+    graph: TransferGraph,
+    slots: Option<(u64, u64)>,
+    address_book: AddressBook,
+}
+
+impl<W: Write> DotPresenter<W> {
+    pub fn new(writer: W, options: GraphOptions, address_book: AddressBook) -> Self {
+        Self {
+            writer,
+            options,
+            graph: TransferGraph::new(options.merge_parallel),
+            slots: None,
+            address_book,
+        }
+    }
+}
+
+impl<W: Write> Presenter for DotPresenter<W> {
+    fn present(&mut self, block: &Block) -> Result<()> {
+        for transaction in &block.transactions {
+            self.graph.add_transaction(transaction);
+        }
+
+        self.slots = Some(match self.slots {
+            Some((first, last)) => (first.min(block.slot), last.max(block.slot)),
+            None => (block.slot, block.slot),
+        });
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let mut graph = std::mem::take(&mut self.graph).without_dust(self.options.dust);
+        if let Some(top) = self.options.top {
+            graph = graph.top_nodes(top);
+        }
+
+        writeln!(self.writer, "digraph transfers {{")?;
+        match self.slots {
+            Some((first, last)) if first != last => {
+                writeln!(self.writer, "    label=\"Slots {} to {}\";", first, last)?
+            }
+            Some((slot, _)) => writeln!(self.writer, "    label=\"Slot {}\";", slot)?,
+            None => {}
+        }
+        writeln!(self.writer, "    labelloc=t;")?;
+        writeln!(self.writer, "    rankdir=LR;")?;
+        writeln!(
+            self.writer,
+            "    node [shape=box, style=rounded, fontname=\"monospace\"];"
+        )?;
+        writeln!(self.writer, "    edge [fontname=\"monospace\"];")?;
+
+        for node in graph.nodes() {
+            writeln!(
+                self.writer,
+                "    {} [label={}, tooltip={}];",
+                quote(&node.address),
+                quote(&self.address_book.label(&node.address)),
+                quote(&node.address)
+            )?;
+        }
+
+        for edge in &graph.edges {
+            let mut label = format!("{} {}", edge.amount, edge.token.label());
+            if edge.count > 1 {
+                label.push_str(&format!(" ({} transfers)", edge.count));
+            }
+            // Logarithmic so that a whale transfer does not swallow the picture.
+            let penwidth = (1.0 + (1.0 + edge.amount.to_f64()).log10()).min(8.0);
+
+            writeln!(
+                self.writer,
+                "    {} -> {} [label={}, penwidth={:.1}];",
+                quote(&edge.source),
+                quote(&edge.destination),
+                quote(&label),
+                penwidth
+            )?;
+        }
+
+        writeln!(self.writer, "}}")?;
+
+        Ok(self.writer.flush()?)
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::amount::Amount;
+    use crate::domain::mint::Mint;
+    use crate::domain::program::Program;
+    use crate::domain::transaction::Transaction;
+
+    fn block(slot: u64, transfers: &[(&str, &str, u64)]) -> Block {
+        let mut block = Block::new(slot, "hash".to_string());
+
+        for (source, destination, amount) in transfers {
+            block.add_transaction(Transaction::new(
+                "sig".to_string(),
+                Account::new(*source, 1, Amount::zero(6)),
+                Account::new(*destination, 2, Amount::zero(6)),
+                Program::new("program", 3),
+                "USDC".parse::<Mint>().unwrap(),
+                Amount::new(*amount, 6),
+            ));
+        }
+
+        block
+    }
+
+    #[test]
+    fn test_present_dot() {
+        let options = GraphOptions {
+            merge_parallel: true,
+            dust: 0.5,
+            top: None,
+        };
+        let mut output: Vec<u8> = Vec::new();
+        let mut presenter = DotPresenter::new(&mut output, options, AddressBook::new());
+
+        presenter
+            .present(&block(
+                7,
+                &[("alice", "bob", 1_000_000), ("bob", "x\"y", 1)],
+            ))
+            .unwrap();
+        presenter
+            .present(&block(9, &[("alice", "bob", 2_500_000)]))
+            .unwrap();
+        assert!(presenter.writer.is_empty());
+        presenter.finish().unwrap();
+        drop(presenter);
+
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with("digraph transfers {\n    label=\"Slots 7 to 9\";"));
+        assert!(output.contains(
+            "    \"alice\" -> \"bob\" [label=\"3.5 USDC (2 transfers)\", penwidth=1.7];"
+        ));
+        assert!(!output.contains("x\\\"y"));
+        assert!(output.ends_with("}\n"));
+    }
+}
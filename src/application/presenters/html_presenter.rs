@@ -0,0 +1,270 @@
+use crate::application::presenters::{Presenter, SCHEMA_VERSION};
+use crate::domain::address_book::AddressBook;
+use crate::domain::amount::Volume;
+use crate::domain::block::Block;
+use crate::domain::transfer_graph::TransferGraph;
+use anyhow::Result;
+use serde::Serialize;
+use std::io::Write;
+
+const TEMPLATE: &str = include_str!("html_report.html");
+const DATA_PLACEHOLDER: &str = "/*REPORT_DATA*/";
+
+#[derive(Serialize)]
+struct Report {
+    schema_version: u32,
+    blocks: Vec<BlockRow>,
+    totals: Vec<TotalRow>,
+    transfers: Vec<TransferRow>,
+    graph: GraphData,
+}
+
+#[derive(Serialize)]
+struct BlockRow {
+    slot: u64,
+    blockhash: String,
+    time: Option<String>,
+    transfers: usize,
+}
+
+#[derive(Serialize)]
+struct TotalRow {
+    token: String,
+    mint: String,
+    amount: String,
+    count: usize,
+    #[serde(skip)]
+    volume: Volume,
+}
+
+#[derive(Serialize)]
+struct TransferRow {
+    slot: u64,
+    signature: String,
+    source: String,
+    destination: String,
+    source_label: Option<String>,
+    destination_label: Option<String>,
+    token: String,
+    amount: String,
+    // Numeric copies only used to sort the table.
+    value: f64,
+    fee: f64,
+    fee_amount: Option<String>,
+}
+
+#[derive(Serialize, Default)]
+struct GraphData {
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+}
+
+#[derive(Serialize)]
+struct GraphNode {
+    address: String,
+    label: String,
+    volume: f64,
+}
+
+#[derive(Serialize)]
+struct GraphEdge {
+    source: String,
+    destination: String,
+    label: String,
+    width: f64,
+}
+
+// Collects every block and writes one self-contained HTML page once the last
+// block was presented. The data is embedded as JSON next to the script, so the
+// page opens without network access.
+pub struct HtmlPresenter<W> {
+    writer: W,
+    blocks: Vec<BlockRow>,
+    totals: Vec<TotalRow>,
+    transfers: Vec<TransferRow>,
+    graph: TransferGraph,
+    address_book: AddressBook,
+}
+
+impl<W: Write> HtmlPresenter<W> {
+    pub fn new(writer: W, address_book: AddressBook) -> Self {
+        Self {
+            writer,
+            blocks: Vec::new(),
+            totals: Vec::new(),
+            transfers: Vec::new(),
+            graph: TransferGraph::new(true),
+            address_book,
+        }
+    }
+}
+
+impl<W: Write> Presenter for HtmlPresenter<W> {
+    fn present(&mut self, block: &Block) -> Result<()> {
+        self.blocks.push(BlockRow {
+            slot: block.slot,
+            blockhash: block.blockhash.clone(),
+            time: block
+                .block_time
+                .and_then(|time| chrono::DateTime::from_timestamp(time, 0))
+                .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string()),
+            transfers: block.transactions.len(),
+        });
+
+        for transaction in &block.transactions {
+            let amount = transaction.amount();
+            let label = transaction.token.label();
+
+            match self
+                .totals
+                .iter_mut()
+                .find(|total| total.mint == transaction.token.address)
+            {
+                Some(total) => {
+                    total.volume.add(amount);
+                    total.amount = total.volume.to_string();
+                    total.count += 1;
+                }
+                None => {
+                    let mut volume = Volume::new(0, amount.decimals);
+                    volume.add(amount);
+                    self.totals.push(TotalRow {
+                        token: label.clone(),
+                        mint: transaction.token.address.clone(),
+                        amount: volume.to_string(),
+                        count: 1,
+                        volume,
+                    })
+                }
+            }
+
+            self.transfers.push(TransferRow {
+                slot: block.slot,
+                signature: transaction.signature.clone(),
+                source: transaction.source.address.clone(),
+                destination: transaction.destination.address.clone(),
+                source_label: self
+                    .address_book
+                    .get(&transaction.source.address)
+                    .map(str::to_string),
+                destination_label: self
+                    .address_book
+                    .get(&transaction.destination.address)
+                    .map(str::to_string),
+                token: label,
+                amount: amount.to_string(),
+                value: amount.to_f64(),
+                fee: transaction.fee.map(|fee| fee.to_f64()).unwrap_or(0.0),
+                fee_amount: transaction.fee.map(|fee| fee.to_string()),
+            });
+            self.graph.add_transaction(transaction);
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let graph = std::mem::take(&mut self.graph);
+        let graph = GraphData {
+            nodes: graph
+                .nodes()
+                .into_iter()
+                .map(|node| GraphNode {
+                    label: self.address_book.label(&node.address),
+                    address: node.address,
+                    volume: node.volume,
+                })
+                .collect(),
+            edges: graph
+                .edges
+                .iter()
+                .map(|edge| GraphEdge {
+                    source: edge.source.clone(),
+                    destination: edge.destination.clone(),
+                    label: format!("{} {}", edge.amount, edge.token.label()),
+                    width: (1.0 + (1.0 + edge.amount.to_f64()).log10()).min(8.0),
+                })
+                .collect(),
+        };
+        let report = Report {
+            schema_version: SCHEMA_VERSION,
+            blocks: std::mem::take(&mut self.blocks),
+            totals: std::mem::take(&mut self.totals),
+            transfers: std::mem::take(&mut self.transfers),
+            graph,
+        };
+
+        // A literal `</script>` inside the data would end the script element early.
+        let data = serde_json::to_string(&report)?.replace("</", "<\\/");
+
+        self.writer
+            .write_all(TEMPLATE.replace(DATA_PLACEHOLDER, &data).as_bytes())?;
+
+        Ok(self.writer.flush()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::amount::Amount;
+    use crate::domain::mint::Mint;
+    use crate::domain::program::Program;
+    use crate::domain::transaction::Transaction;
+
+    #[test]
+    fn test_present_html() {
+        let mut block = Block::new(7, "hash".to_string());
+        block.update_block_time(Some(1_717_200_000));
+        for amount in [1_000_000, 500_000] {
+            block.add_transaction(Transaction::new(
+                "sig</script>".to_string(),
+                Account::new("alice", 1, Amount::zero(6)),
+                Account::new("bob", 2, Amount::zero(6)),
+                Program::new("program", 3),
+                "USDC".parse::<Mint>().unwrap(),
+                Amount::new(amount, 6),
+            ));
+        }
+
+        let mut output: Vec<u8> = Vec::new();
+        let mut presenter = HtmlPresenter::new(&mut output, AddressBook::new());
+        presenter.present(&block).unwrap();
+        presenter.finish().unwrap();
+        drop(presenter);
+
+        let output = String::from_utf8(output).unwrap();
+        let start = output.find("application/json\">").unwrap() + "application/json\">".len();
+        let end = start + output[start..].find("</script>").unwrap();
+        let data: serde_json::Value = serde_json::from_str(&output[start..end]).unwrap();
+
+        assert!(!output.contains(DATA_PLACEHOLDER));
+        assert!(!output.contains("src=\"http"));
+        assert_eq!(data["blocks"][0]["time"], "2024-06-01 00:00:00");
+        assert_eq!(data["totals"][0]["amount"], "1.5");
+        assert_eq!(data["totals"][0]["count"], 2);
+        assert_eq!(data["transfers"][1]["signature"], "sig</script>");
+        assert_eq!(data["graph"]["edges"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_totals_past_u64() {
+        let mut block = Block::new(7, "hash".to_string());
+        for _ in 0..2 {
+            block.add_transaction(Transaction::new(
+                "sig".to_string(),
+                Account::new("alice", 1, Amount::zero(9)),
+                Account::new("bob", 2, Amount::zero(9)),
+                Program::new("program", 3),
+                "SOL".parse::<Mint>().unwrap(),
+                Amount::new(u64::MAX, 9),
+            ));
+        }
+
+        let mut presenter = HtmlPresenter::new(Vec::new(), AddressBook::new());
+        presenter.present(&block).unwrap();
+
+        assert_eq!(presenter.totals[0].amount, "36893488147.41910323");
+    }
+}
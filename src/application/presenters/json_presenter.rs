@@ -0,0 +1,175 @@
+use crate::application::presenters::{Presenter, SCHEMA_VERSION};
+use crate::domain::address_book::AddressBook;
+use crate::domain::block::Block;
+use crate::domain::commitment::FinalityUpdate;
+use crate::domain::connection::ConnectionState;
+use crate::domain::slot_tracker::MissedSlot;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+#[derive(Serialize)]
+struct BlockRecord<'a> {
+    schema_version: u32,
+    block: &'a Block,
+    labels: BTreeMap<String, String>,
+}
+
+#[derive(Serialize)]
+struct MissedRecord<'a> {
+    schema_version: u32,
+    missed: &'a MissedSlot,
+}
+
+#[derive(Serialize)]
+struct ConnectionRecord<'a> {
+    schema_version: u32,
+    connection: &'a ConnectionState,
+}
+
+#[derive(Serialize)]
+struct FinalityRecord<'a> {
+    schema_version: u32,
+    finality: &'a FinalityUpdate,
+}
+
+// Writes one JSON document per block, either pretty printed or, for JSON
+// Lines, compact on a single line. Addresses stay in full, the known ones are
+// named in a separate `labels` object.
+pub struct JsonPresenter<W> {
+    writer: W,
+    lines: bool,
+    address_book: AddressBook,
+}
+
+impl<W: Write> JsonPresenter<W> {
+    pub fn new(writer: W, address_book: AddressBook) -> Self {
+        Self {
+            writer,
+            lines: false,
+            address_book,
+        }
+    }
+
+    pub fn new_lines(writer: W, address_book: AddressBook) -> Self {
+        Self {
+            writer,
+            lines: true,
+            address_book,
+        }
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    fn write<T: Serialize>(&mut self, record: &T) -> Result<()> {
+        if self.lines {
+            serde_json::to_writer(&mut self.writer, record)?;
+        } else {
+            serde_json::to_writer_pretty(&mut self.writer, record)?;
+        }
+        writeln!(self.writer)?;
+
+        Ok(self.writer.flush()?)
+    }
+}
+
+impl<W: Write> Presenter for JsonPresenter<W> {
+    fn present(&mut self, block: &Block) -> Result<()> {
+        let record = BlockRecord {
+            schema_version: SCHEMA_VERSION,
+            block,
+            labels: self.address_book.labels(block.addresses()),
+        };
+
+        self.write(&record)
+    }
+
+    // A `{"schema_version": 1, "missed": {"slot": …}}` record in place of the
+    // block that could not be fetched.
+    fn missed(&mut self, slot: &MissedSlot) -> Result<()> {
+        self.write(&MissedRecord {
+            schema_version: SCHEMA_VERSION,
+            missed: slot,
+        })
+    }
+
+    // A `{"schema_version": 1, "connection": {"state": …}}` record between
+    // the blocks.
+    fn connection(&mut self, state: &ConnectionState) -> Result<()> {
+        self.write(&ConnectionRecord {
+            schema_version: SCHEMA_VERSION,
+            connection: state,
+        })
+    }
+
+    // A `{"schema_version": 1, "finality": {"slot": …, "finality": …}}` record
+    // once a block presented below finalized settles.
+    fn finality(&mut self, update: &FinalityUpdate) -> Result<()> {
+        self.write(&FinalityRecord {
+            schema_version: SCHEMA_VERSION,
+            finality: update,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::amount::Amount;
+    use crate::domain::mint::Mint;
+    use crate::domain::program::{Program, TOKEN_PROGRAM_ID};
+    use crate::domain::transaction::Transaction;
+
+    #[test]
+    fn test_present_json_lines() {
+        let mut block = Block::new(42, "blockhash".to_string());
+        block.add_transaction(Transaction::new(
+            "signature".to_string(),
+            Account::new("source", 1, Amount::new(100, 6)),
+            Account::new("destination", 2, Amount::new(0, 6)),
+            Program::new(TOKEN_PROGRAM_ID, 3),
+            Mint::new("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
+            Amount::new(18_446_744_073_709_551_615, 6),
+        ));
+
+        let mut output: Vec<u8> = Vec::new();
+        JsonPresenter::new_lines(&mut output, AddressBook::builtin())
+            .present(&block)
+            .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let transaction = &value["block"]["transactions"][0];
+
+        assert_eq!(output.lines().count(), 1);
+        assert_eq!(value["schema_version"], 1);
+        assert_eq!(value["block"]["slot"], 42);
+        assert_eq!(transaction["amount"]["raw"], "18446744073709551615");
+        assert_eq!(transaction["amount"]["ui"], "18446744073709.551615");
+        assert_eq!(transaction["token"]["symbol"], "USDC");
+        assert_eq!(transaction["program"]["kind"], "token");
+        assert_eq!(transaction["fee"], serde_json::Value::Null);
+        assert_eq!(value["labels"][TOKEN_PROGRAM_ID], "Token Program");
+        assert_eq!(value["labels"].as_object().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_connection_record() {
+        let mut output: Vec<u8> = Vec::new();
+        JsonPresenter::new_lines(&mut output, AddressBook::new())
+            .connection(&ConnectionState::Reconnecting {
+                attempt: 2,
+                delay_seconds: 2,
+            })
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\"schema_version\":1,\"connection\":{\"state\":\"reconnecting\",\"attempt\":2,\"delay_seconds\":2}}\n"
+        );
+    }
+}
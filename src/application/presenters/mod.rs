@@ -0,0 +1,104 @@
+pub mod alert_presenter;
+pub mod checkpoint_presenter;
+pub mod csv_presenter;
+pub mod dot_presenter;
+pub mod html_presenter;
+pub mod json_presenter;
+pub mod sankey_presenter;
+pub mod stats_presenter;
+pub mod store_presenter;
+pub mod text_presenter;
+
+use crate::domain::block::Block;
+use crate::domain::commitment::FinalityUpdate;
+use crate::domain::connection::ConnectionState;
+use crate::domain::slot_tracker::MissedSlot;
+use anyhow::Result;
+
+// Version of the serialized block schema shared by the JSON, JSON Lines and
+// CSV outputs. Bump it on any change that is not purely additive.
+pub const SCHEMA_VERSION: u32 = 1;
+
+pub trait Presenter {
+    fn present(&mut self, block: &Block) -> Result<()>;
+
+    // Called for a slot given up on, its block missing from the output.
+    fn missed(&mut self, _slot: &MissedSlot) -> Result<()> {
+        Ok(())
+    }
+
+    // Called when the connection to the cluster drops or comes back, for
+    // live outputs that show it.
+    fn connection(&mut self, _state: &ConnectionState) -> Result<()> {
+        Ok(())
+    }
+
+    // Called when a block presented before it was finalized turns out
+    // finalized or dropped.
+    fn finality(&mut self, _update: &FinalityUpdate) -> Result<()> {
+        Ok(())
+    }
+
+    // Called once after the last block, for outputs that aggregate blocks.
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<P: Presenter + ?Sized> Presenter for Box<P> {
+    fn present(&mut self, block: &Block) -> Result<()> {
+        (**self).present(block)
+    }
+
+    fn missed(&mut self, slot: &MissedSlot) -> Result<()> {
+        (**self).missed(slot)
+    }
+
+    fn connection(&mut self, state: &ConnectionState) -> Result<()> {
+        (**self).connection(state)
+    }
+
+    fn finality(&mut self, update: &FinalityUpdate) -> Result<()> {
+        (**self).finality(update)
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        (**self).finish()
+    }
+}
+
+// Presents nothing, a base for the sinks and alerts of outputs that are not
+// presenters themselves, such as the dashboard.
+impl Presenter for () {
+    fn present(&mut self, _block: &Block) -> Result<()> {
+        Ok(())
+    }
+}
+
+// Presents every block to both, e.g. an output and the alerts.
+impl<A: Presenter, B: Presenter> Presenter for (A, B) {
+    fn present(&mut self, block: &Block) -> Result<()> {
+        self.0.present(block)?;
+        self.1.present(block)
+    }
+
+    fn missed(&mut self, slot: &MissedSlot) -> Result<()> {
+        self.0.missed(slot)?;
+        self.1.missed(slot)
+    }
+
+    fn connection(&mut self, state: &ConnectionState) -> Result<()> {
+        self.0.connection(state)?;
+        self.1.connection(state)
+    }
+
+    fn finality(&mut self, update: &FinalityUpdate) -> Result<()> {
+        self.0.finality(update)?;
+        self.1.finality(update)
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.0.finish()?;
+        self.1.finish()
+    }
+}
@@ -0,0 +1,485 @@
+use crate::application::presenters::dot_presenter::GraphOptions;
+use crate::application::presenters::Presenter;
+use crate::domain::address_book::AddressBook;
+use crate::domain::amount::Amount;
+use crate::domain::block::Block;
+use crate::domain::transfer_graph::{Edge, TransferGraph};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::Write;
+
+const MARGIN: f64 = 40.0;
+const COLUMN_WIDTH: f64 = 260.0;
+const NODE_WIDTH: f64 = 14.0;
+const NODE_PADDING: f64 = 14.0;
+const ROW_HEIGHT: f64 = 40.0;
+const MIN_SECTION_HEIGHT: f64 = 240.0;
+const TITLE_HEIGHT: f64 = 36.0;
+const COLORS: [&str; 8] = [
+    "#0969da", "#1a7f37", "#9a6700", "#cf222e", "#8250df", "#bf3989", "#0a7d8c", "#6e7781",
+];
+
+// Aggregates the transfers of every block into one Sankey diagram per mint and
+// writes them as a standalone SVG once the last block was presented. Owners are
+// laid out in columns following the direction of the money.
+pub struct SankeyPresenter<W> {
+    writer: W,
+    options: GraphOptions,
+    address_book: AddressBook,
+    graph: TransferGraph,
+}
+
+impl<W: Write> SankeyPresenter<W> {
+    pub fn new(writer: W, options: GraphOptions, address_book: AddressBook) -> Self {
+        Self {
+            writer,
+            options,
+            address_book,
+            graph: TransferGraph::new(true),
+        }
+    }
+}
+
+impl<W: Write> Presenter for SankeyPresenter<W> {
+    fn present(&mut self, block: &Block) -> Result<()> {
+        for transaction in &block.transactions {
+            self.graph.add_transaction(transaction);
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let mut graph = std::mem::take(&mut self.graph).without_dust(self.options.dust);
+        if let Some(top) = self.options.top {
+            graph = graph.top_nodes(top);
+        }
+
+        let mut mints: Vec<&str> = Vec::new();
+        for edge in &graph.edges {
+            if !mints.contains(&edge.token.address.as_str()) {
+                mints.push(&edge.token.address);
+            }
+        }
+
+        let mut body = String::new();
+        let mut width = 2.0 * MARGIN + COLUMN_WIDTH;
+        let mut height = MARGIN;
+
+        for mint in mints {
+            let edges: Vec<&Edge> = graph
+                .edges
+                .iter()
+                .filter(|edge| edge.token.address == mint)
+                .collect();
+            let section = Section::layout(&edges);
+
+            width = width.max(section.width());
+            height += section.render(&mut body, &self.address_book, height)? + MARGIN;
+        }
+
+        if body.is_empty() {
+            writeln!(
+                body,
+                "  <text x=\"{}\" y=\"{}\" class=\"empty\">No transfers.</text>",
+                MARGIN, MARGIN
+            )?;
+            height += MARGIN;
+        }
+
+        writeln!(
+            self.writer,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" viewBox=\"0 0 {w:.0} {h:.0}\">",
+            w = width,
+            h = height
+        )?;
+        writeln!(self.writer, "  <style>")?;
+        writeln!(
+            self.writer,
+            "    text {{ font: 12px -apple-system, \"Segoe UI\", Helvetica, Arial, sans-serif; fill: #1f2328; }}"
+        )?;
+        writeln!(
+            self.writer,
+            "    .title {{ font-size: 16px; font-weight: 600; }}"
+        )?;
+        writeln!(
+            self.writer,
+            "    .amount, .empty, .note {{ fill: #59636e; }}"
+        )?;
+        writeln!(
+            self.writer,
+            "    .link {{ fill: none; stroke-opacity: 0.35; }}"
+        )?;
+        writeln!(self.writer, "    .link:hover {{ stroke-opacity: 0.6; }}")?;
+        writeln!(self.writer, "  </style>")?;
+        writeln!(
+            self.writer,
+            "  <rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>"
+        )?;
+        self.writer.write_all(body.as_bytes())?;
+        writeln!(self.writer, "</svg>")?;
+
+        Ok(self.writer.flush()?)
+    }
+}
+
+struct Flow {
+    source: usize,
+    destination: usize,
+    amount: Amount,
+}
+
+// Layout of a single mint: owners placed in columns, flows between them.
+struct Section {
+    label: String,
+    addresses: Vec<String>,
+    layers: Vec<usize>,
+    throughput: Vec<Amount>,
+    flows: Vec<Flow>,
+    omitted: usize,
+}
+
+impl Section {
+    fn layout(edges: &[&Edge]) -> Self {
+        let mut addresses: Vec<String> = Vec::new();
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        let mut flows = Vec::new();
+        let mut omitted = 0;
+
+        for edge in edges {
+            if edge.source == edge.destination {
+                omitted += 1;
+                continue;
+            }
+
+            let [source, destination] = [&edge.source, &edge.destination].map(|address| {
+                *index.entry(address).or_insert_with(|| {
+                    addresses.push(address.clone());
+                    addresses.len() - 1
+                })
+            });
+
+            flows.push(Flow {
+                source,
+                destination,
+                amount: edge.amount,
+            });
+        }
+
+        let pairs: Vec<(usize, usize)> = flows
+            .iter()
+            .map(|flow| (flow.source, flow.destination))
+            .collect();
+        let (layers, kept) = layers(addresses.len(), &pairs);
+
+        // Flows that close a cycle cannot be drawn left to right and are left out.
+        omitted += kept.iter().filter(|kept| !**kept).count();
+        let flows: Vec<Flow> = flows
+            .into_iter()
+            .zip(kept)
+            .filter_map(|(flow, kept)| kept.then_some(flow))
+            .collect();
+
+        let decimals = edges
+            .first()
+            .map(|edge| edge.amount.decimals)
+            .unwrap_or_default();
+        let mut inflow = vec![Amount::zero(decimals); addresses.len()];
+        let mut outflow = vec![Amount::zero(decimals); addresses.len()];
+        for flow in &flows {
+            outflow[flow.source] = add(outflow[flow.source], flow.amount);
+            inflow[flow.destination] = add(inflow[flow.destination], flow.amount);
+        }
+        let throughput = inflow
+            .into_iter()
+            .zip(outflow)
+            .map(|(inflow, outflow)| {
+                if inflow.raw > outflow.raw {
+                    inflow
+                } else {
+                    outflow
+                }
+            })
+            .collect();
+
+        Self {
+            label: edges
+                .first()
+                .map(|edge| edge.token.label())
+                .unwrap_or_default(),
+            addresses,
+            layers,
+            throughput,
+            flows,
+            omitted,
+        }
+    }
+
+    fn columns(&self) -> Vec<Vec<usize>> {
+        let count = self.layers.iter().max().map_or(0, |layer| layer + 1);
+        let mut columns = vec![Vec::new(); count];
+
+        for (node, layer) in self.layers.iter().enumerate() {
+            columns[*layer].push(node);
+        }
+        for column in &mut columns {
+            column.sort_by(|a, b| self.throughput[*b].raw.cmp(&self.throughput[*a].raw));
+        }
+
+        columns
+    }
+
+    fn width(&self) -> f64 {
+        let columns = self.columns().len().max(1) as f64;
+        2.0 * MARGIN + (columns - 1.0) * COLUMN_WIDTH + NODE_WIDTH + COLUMN_WIDTH
+    }
+
+    // Renders the section below `top` and returns its height.
+    fn render(&self, out: &mut String, address_book: &AddressBook, top: f64) -> Result<f64> {
+        let columns = self.columns();
+        let rows = columns.iter().map(Vec::len).max().unwrap_or(0) as f64;
+        let height = MIN_SECTION_HEIGHT.max(rows * ROW_HEIGHT);
+        let scale = columns
+            .iter()
+            .map(|column| {
+                let total: f64 = column
+                    .iter()
+                    .map(|node| self.throughput[*node].to_f64())
+                    .sum();
+                let padding = NODE_PADDING * column.len().saturating_sub(1) as f64;
+                (height - padding) / total.max(f64::MIN_POSITIVE)
+            })
+            .fold(f64::INFINITY, f64::min);
+
+        let mut x = vec![0.0; self.addresses.len()];
+        let mut y = vec![0.0; self.addresses.len()];
+        let mut node_height = vec![0.0; self.addresses.len()];
+        for (layer, column) in columns.iter().enumerate() {
+            let mut offset = top + TITLE_HEIGHT;
+            for node in column {
+                x[*node] = MARGIN + layer as f64 * COLUMN_WIDTH;
+                y[*node] = offset;
+                node_height[*node] = (self.throughput[*node].to_f64() * scale).max(1.0);
+                offset += node_height[*node] + NODE_PADDING;
+            }
+        }
+
+        writeln!(out, "  <g class=\"sankey\">")?;
+        writeln!(
+            out,
+            "    <text x=\"{}\" y=\"{:.1}\" class=\"title\">{} flows</text>",
+            MARGIN,
+            top + 16.0,
+            escape(&self.label)
+        )?;
+        if self.omitted > 0 {
+            writeln!(
+                out,
+                "    <text x=\"{}\" y=\"{:.1}\" class=\"note\">{} circular or self transfers not shown</text>",
+                MARGIN,
+                top + 30.0,
+                self.omitted
+            )?;
+        }
+
+        // Links leave and enter nodes stacked in the order of the other end, so
+        // ribbons do not cross more than needed.
+        let mut order: Vec<&Flow> = self.flows.iter().collect();
+        order.sort_by(|a, b| {
+            y[a.source]
+                .total_cmp(&y[b.source])
+                .then(y[a.destination].total_cmp(&y[b.destination]))
+        });
+        let mut out_offset = vec![0.0; self.addresses.len()];
+        let mut in_offset = vec![0.0; self.addresses.len()];
+        for flow in order {
+            let width = (flow.amount.to_f64() * scale).max(1.0);
+            let x0 = x[flow.source] + NODE_WIDTH;
+            let x1 = x[flow.destination];
+            let y0 = y[flow.source] + out_offset[flow.source] + width / 2.0;
+            let y1 = y[flow.destination] + in_offset[flow.destination] + width / 2.0;
+            let middle = (x0 + x1) / 2.0;
+            out_offset[flow.source] += width;
+            in_offset[flow.destination] += width;
+
+            writeln!(
+                out,
+                "    <path class=\"link\" d=\"M{:.1},{:.1} C{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}\" stroke=\"{}\" stroke-width=\"{:.1}\"><title>{} → {}: {} {}</title></path>",
+                x0,
+                y0,
+                middle,
+                y0,
+                middle,
+                y1,
+                x1,
+                y1,
+                COLORS[flow.source % COLORS.len()],
+                width,
+                escape(&address_book.label(&self.addresses[flow.source])),
+                escape(&address_book.label(&self.addresses[flow.destination])),
+                flow.amount,
+                escape(&self.label)
+            )?;
+        }
+
+        for (node, address) in self.addresses.iter().enumerate() {
+            let label = address_book.label(address);
+            writeln!(
+                out,
+                "    <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{}\" height=\"{:.1}\" fill=\"{}\"><title>{}</title></rect>",
+                x[node],
+                y[node],
+                NODE_WIDTH,
+                node_height[node],
+                COLORS[node % COLORS.len()],
+                escape(address)
+            )?;
+            writeln!(
+                out,
+                "    <text x=\"{:.1}\" y=\"{:.1}\" dy=\"0.35em\">{} <tspan class=\"amount\">{}</tspan></text>",
+                x[node] + NODE_WIDTH + 6.0,
+                y[node] + node_height[node] / 2.0,
+                escape(&label),
+                self.throughput[node]
+            )?;
+        }
+        writeln!(out, "  </g>")?;
+
+        Ok(TITLE_HEIGHT + height)
+    }
+}
+
+// Assigns every node the length of the longest path leading to it. Edges that
+// would close a cycle are found with a depth-first search and reported as not
+// kept, since a Sankey diagram has to be acyclic.
+fn layers(count: usize, edges: &[(usize, usize)]) -> (Vec<usize>, Vec<bool>) {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        New,
+        Active,
+        Done,
+    }
+
+    let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); count];
+    for (edge, (source, _)) in edges.iter().enumerate() {
+        outgoing[*source].push(edge);
+    }
+
+    let mut kept = vec![true; edges.len()];
+    let mut state = vec![State::New; count];
+    let mut order = Vec::with_capacity(count);
+
+    for root in 0..count {
+        if state[root] != State::New {
+            continue;
+        }
+
+        let mut stack = vec![(root, 0)];
+        state[root] = State::Active;
+
+        while let Some((node, next)) = stack.last_mut() {
+            let node = *node;
+            match outgoing[node].get(*next) {
+                Some(edge) => {
+                    *next += 1;
+                    let destination = edges[*edge].1;
+                    match state[destination] {
+                        State::New => {
+                            state[destination] = State::Active;
+                            stack.push((destination, 0));
+                        }
+                        State::Active => kept[*edge] = false,
+                        State::Done => {}
+                    }
+                }
+                None => {
+                    state[node] = State::Done;
+                    order.push(node);
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    let mut layers = vec![0; count];
+    for node in order.into_iter().rev() {
+        for edge in &outgoing[node] {
+            if kept[*edge] {
+                let destination = edges[*edge].1;
+                layers[destination] = layers[destination].max(layers[node] + 1);
+            }
+        }
+    }
+
+    (layers, kept)
+}
+
+fn add(total: Amount, amount: Amount) -> Amount {
+    total
+        .checked_add(amount)
+        .unwrap_or(Amount::new(u64::MAX, amount.decimals))
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::address_book::AddressKind;
+    use crate::domain::mint::Mint;
+    use crate::domain::program::Program;
+    use crate::domain::transaction::Transaction;
+
+    #[test]
+    fn test_layers_break_cycles() {
+        let (layers, kept) = layers(4, &[(0, 1), (1, 2), (0, 2), (2, 0), (3, 2)]);
+
+        assert_eq!(layers, vec![0, 1, 2, 0]);
+        assert_eq!(kept, vec![true, true, true, false, true]);
+    }
+
+    #[test]
+    fn test_present_sankey() {
+        let mut block = Block::new(7, "hash".to_string());
+        for (source, destination, amount) in [
+            ("exchange", "alice", 3_000_000),
+            ("alice", "bob", 1_000_000),
+            ("alice", "<carol>", 2_000_000),
+        ] {
+            block.add_transaction(Transaction::new(
+                "sig".to_string(),
+                Account::new(source, 1, Amount::zero(6)),
+                Account::new(destination, 2, Amount::zero(6)),
+                Program::new("program", 3),
+                "USDC".parse::<Mint>().unwrap(),
+                Amount::new(amount, 6),
+            ));
+        }
+        let mut address_book = AddressBook::new();
+        address_book.insert("exchange", "Hot Wallet", AddressKind::Exchange);
+
+        let mut output: Vec<u8> = Vec::new();
+        let mut presenter =
+            SankeyPresenter::new(&mut output, GraphOptions::default(), address_book);
+        presenter.present(&block).unwrap();
+        presenter.finish().unwrap();
+        drop(presenter);
+
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(output.ends_with("</svg>\n"));
+        assert!(output.contains(">USDC flows</text>"));
+        assert!(output.contains(">Hot Wallet <tspan class=\"amount\">3</tspan>"));
+        assert!(output.contains("&lt;carol&gt;"));
+        assert_eq!(output.matches("<path class=\"link\"").count(), 3);
+    }
+}
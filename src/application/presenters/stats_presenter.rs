@@ -0,0 +1,215 @@
+use crate::application::presenters::{Presenter, SCHEMA_VERSION};
+use crate::domain::address_book::AddressBook;
+use crate::domain::block::Block;
+use crate::domain::stats::{Counterparty, TransferStats, WindowStats, WINDOWS};
+use anyhow::Result;
+use serde::Serialize;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+const TOP_COUNTERPARTIES: usize = 5;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum StatsOutput {
+    Text,
+    Json,
+    Jsonl,
+}
+
+#[derive(Serialize)]
+struct StatsRecord<'a> {
+    schema_version: u32,
+    latest_slot: Option<u64>,
+    stats: &'a [WindowStats],
+}
+
+// Keeps rolling statistics of the transfers and reports them over the 1m, 5m
+// and 1h windows every `interval`, and once more at the end.
+pub struct StatsPresenter<W> {
+    writer: W,
+    output: StatsOutput,
+    interval: Duration,
+    address_book: AddressBook,
+    stats: TransferStats,
+    latest_slot: Option<u64>,
+    last_report: Option<Instant>,
+}
+
+impl<W: Write> StatsPresenter<W> {
+    pub fn new(
+        writer: W,
+        output: StatsOutput,
+        interval: Duration,
+        address_book: AddressBook,
+    ) -> Self {
+        Self {
+            writer,
+            output,
+            interval,
+            address_book,
+            stats: TransferStats::new(WINDOWS[WINDOWS.len() - 1]),
+            latest_slot: None,
+            last_report: None,
+        }
+    }
+
+    fn report(&mut self) -> Result<()> {
+        let windows = self.stats.windows(TOP_COUNTERPARTIES);
+        let record = StatsRecord {
+            schema_version: SCHEMA_VERSION,
+            latest_slot: self.latest_slot,
+            stats: &windows,
+        };
+
+        match self.output {
+            StatsOutput::Json => {
+                serde_json::to_writer_pretty(&mut self.writer, &record)?;
+                writeln!(self.writer)?;
+            }
+            StatsOutput::Jsonl => {
+                serde_json::to_writer(&mut self.writer, &record)?;
+                writeln!(self.writer)?;
+            }
+            StatsOutput::Text => self.write_text(&windows)?,
+        }
+
+        Ok(self.writer.flush()?)
+    }
+
+    fn write_text(&mut self, windows: &[WindowStats]) -> Result<()> {
+        writeln!(
+            self.writer,
+            "------------------------------------------------------------"
+        )?;
+        match self.latest_slot {
+            Some(slot) => writeln!(self.writer, "Stats at slot {}", slot)?,
+            None => writeln!(self.writer, "Stats, no block yet")?,
+        }
+
+        for window in windows {
+            writeln!(
+                self.writer,
+                "  {}: {} transfers in {} slots ({:.2} per slot), {} senders, {} receivers",
+                window_label(window.window_seconds),
+                window.transfers,
+                window.slots,
+                window.transfers_per_slot,
+                window.unique_senders,
+                window.unique_receivers
+            )?;
+
+            for mint in &window.mints {
+                let token = mint.token.label();
+                writeln!(
+                    self.writer,
+                    "    {}: volume {} ({} per minute), median {}, p99 {}",
+                    token, mint.volume, mint.volume_per_minute, mint.median, mint.p99
+                )?;
+                writeln!(
+                    self.writer,
+                    "      top senders: {}",
+                    self.counterparties(&mint.top_senders)
+                )?;
+                writeln!(
+                    self.writer,
+                    "      top receivers: {}",
+                    self.counterparties(&mint.top_receivers)
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn counterparties(&self, counterparties: &[Counterparty]) -> String {
+        counterparties
+            .iter()
+            .map(|counterparty| {
+                format!(
+                    "{} {}",
+                    self.address_book.label(&counterparty.address),
+                    counterparty.amount
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+fn window_label(seconds: u64) -> String {
+    match seconds {
+        seconds if seconds % 3600 == 0 => format!("{}h", seconds / 3600),
+        seconds if seconds % 60 == 0 => format!("{}m", seconds / 60),
+        seconds => format!("{}s", seconds),
+    }
+}
+
+impl<W: Write> Presenter for StatsPresenter<W> {
+    fn present(&mut self, block: &Block) -> Result<()> {
+        let now = Instant::now();
+        self.stats.record(block, chrono::Utc::now().timestamp());
+        self.latest_slot = Some(block.slot);
+
+        match self.last_report {
+            Some(last_report) if now.duration_since(last_report) < self.interval => Ok(()),
+            Some(_) => {
+                self.last_report = Some(now);
+                self.report()
+            }
+            None => {
+                self.last_report = Some(now);
+                Ok(())
+            }
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.report()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::amount::Amount;
+    use crate::domain::mint::Mint;
+    use crate::domain::program::Program;
+    use crate::domain::transaction::Transaction;
+
+    #[test]
+    fn test_present_stats() {
+        let mut block = Block::new(9, "hash".to_string());
+        block.add_transaction(Transaction::new(
+            "sig".to_string(),
+            Account::new("alice", 1, Amount::zero(6)),
+            Account::new("bob", 2, Amount::zero(6)),
+            Program::new("program", 3),
+            "USDC".parse::<Mint>().unwrap(),
+            Amount::new(2_500_000, 6),
+        ));
+        let mut output: Vec<u8> = Vec::new();
+        let mut presenter = StatsPresenter::new(
+            &mut output,
+            StatsOutput::Jsonl,
+            Duration::from_secs(60),
+            AddressBook::new(),
+        );
+
+        presenter.present(&block).unwrap();
+        presenter.finish().unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let value: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(output.lines().count(), 1);
+        assert_eq!(value["latest_slot"], 9);
+        assert_eq!(value["stats"].as_array().unwrap().len(), 3);
+        assert_eq!(value["stats"][2]["window_seconds"], 3600);
+        assert_eq!(value["stats"][0]["mints"][0]["median"]["ui"], "2.5");
+        assert_eq!(
+            value["stats"][0]["mints"][0]["top_receivers"][0]["address"],
+            "bob"
+        );
+        assert_eq!(window_label(300), "5m");
+    }
+}
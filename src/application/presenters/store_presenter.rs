@@ -0,0 +1,29 @@
+use crate::application::gateways::block_store::BlockStore;
+use crate::application::presenters::Presenter;
+use crate::domain::block::Block;
+use crate::domain::commitment::{Finality, FinalityUpdate};
+use anyhow::Result;
+
+// Saves every block to a store, e.g. next to the printed output.
+pub struct StorePresenter<S> {
+    store: S,
+}
+
+impl<S: BlockStore> StorePresenter<S> {
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+}
+
+impl<S: BlockStore> Presenter for StorePresenter<S> {
+    fn present(&mut self, block: &Block) -> Result<()> {
+        self.store.save(block)
+    }
+
+    fn finality(&mut self, update: &FinalityUpdate) -> Result<()> {
+        match update.finality {
+            Finality::Finalized => Ok(()),
+            Finality::Dropped => self.store.remove(update.slot),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::application::presenters::Presenter;
+use crate::domain::address_book::AddressBook;
+use crate::domain::block::Block;
+use crate::domain::commitment::{Commitment, Finality, FinalityUpdate};
+use crate::domain::connection::ConnectionState;
+use crate::domain::slot_tracker::MissedSlot;
+use anyhow::Result;
+use std::io::Write;
+
+pub struct TextPresenter<W> {
+    writer: W,
+    address_book: AddressBook,
+}
+
+impl<W: Write> TextPresenter<W> {
+    pub fn new(writer: W, address_book: AddressBook) -> Self {
+        Self {
+            writer,
+            address_book,
+        }
+    }
+}
+
+impl<W: Write> Presenter for TextPresenter<W> {
+    fn present(&mut self, block: &Block) -> Result<()> {
+        writeln!(
+            self.writer,
+            "------------------------------------------------------------"
+        )?;
+        if block.commitment == Commitment::Finalized {
+            writeln!(self.writer, "Latest block: {:?}", block.slot)?;
+        } else {
+            writeln!(
+                self.writer,
+                "Latest block: {:?} ({})",
+                block.slot, block.commitment
+            )?;
+        }
+        for transaction in &block.transactions {
+            writeln!(
+                self.writer,
+                "TX {:?} detected: {:?} sent {} {} to {:?}",
+                transaction.signature,
+                self.address_book.label(&transaction.source.address),
+                transaction.amount(),
+                transaction.token.label(),
+                self.address_book.label(&transaction.destination.address)
+            )?;
+            if let Some(fee) = transaction.fee {
+                writeln!(
+                    self.writer,
+                    "    withheld fee: {} {}",
+                    fee,
+                    transaction.token.label()
+                )?;
+            }
+        }
+
+        Ok(self.writer.flush()?)
+    }
+
+    fn missed(&mut self, slot: &MissedSlot) -> Result<()> {
+        writeln!(
+            self.writer,
+            "Gave up on slot {:?} after {} attempts: {}",
+            slot.slot, slot.attempts, slot.error
+        )?;
+
+        Ok(self.writer.flush()?)
+    }
+
+    fn connection(&mut self, state: &ConnectionState) -> Result<()> {
+        writeln!(self.writer, "Connection {}", state)?;
+
+        Ok(self.writer.flush()?)
+    }
+
+    fn finality(&mut self, update: &FinalityUpdate) -> Result<()> {
+        match update.finality {
+            Finality::Finalized => writeln!(self.writer, "Block {:?} finalized", update.slot)?,
+            Finality::Dropped => writeln!(
+                self.writer,
+                "Block {:?} dropped, its transfers did not happen",
+                update.slot
+            )?,
+        }
+
+        Ok(self.writer.flush()?)
+    }
+}
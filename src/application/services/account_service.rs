@@ -0,0 +1,209 @@
+use crate::application::gateways::block_gateway::BlockGateway;
+use crate::application::presenters::Presenter;
+use crate::domain::account_history::{merge_signatures, AccountHistory, Direction};
+use crate::domain::address_book::AddressBook;
+use crate::domain::block::Block;
+use crate::domain::mint::Mint;
+use crate::domain::transaction_detail::TransactionDetail;
+use anyhow::{anyhow, Result};
+use futures_util::stream::{self, StreamExt};
+use std::sync::Arc;
+
+const SIGNATURES_PAGE_SIZE: usize = 1000;
+
+pub struct AccountService<G> {
+    block_gateway: Arc<G>,
+    mints: Vec<Mint>,
+    concurrency: usize,
+    address_book: AddressBook,
+}
+
+impl<G: BlockGateway + Send + Sync + 'static> AccountService<G> {
+    pub fn new(
+        block_gateway: G,
+        mints: Vec<Mint>,
+        concurrency: usize,
+        address_book: AddressBook,
+    ) -> Self {
+        Self {
+            block_gateway: Arc::new(block_gateway),
+            mints,
+            concurrency: concurrency.max(1),
+            address_book,
+        }
+    }
+
+    pub async fn history(&self, owner: &str, limit: usize) -> Result<()> {
+        let history = self.get_history(owner, limit).await?;
+
+        println!("------------------------------------------------------------");
+        println!("History of {}", history.owner);
+        for entry in &history.entries {
+            let transaction = &entry.transaction;
+            let token = transaction.token.label();
+
+            match entry.direction {
+                Direction::Incoming => println!(
+                    "[{}] IN  +{} {} from {:?}, balance {} {} ({})",
+                    entry.slot,
+                    transaction.amount(),
+                    token,
+                    self.address_book.label(&transaction.source.address),
+                    entry.balance,
+                    token,
+                    transaction.signature
+                ),
+                Direction::Outgoing => println!(
+                    "[{}] OUT -{} {} to {:?}, balance {} {} ({})",
+                    entry.slot,
+                    transaction.amount(),
+                    token,
+                    self.address_book.label(&transaction.destination.address),
+                    entry.balance,
+                    token,
+                    transaction.signature
+                ),
+                Direction::Internal => println!(
+                    "[{}] SELF {} {}, balance {} {} ({})",
+                    entry.slot,
+                    transaction.amount(),
+                    token,
+                    entry.balance,
+                    token,
+                    transaction.signature
+                ),
+            }
+        }
+
+        Ok(())
+    }
+
+    // Hands the transfers of the history to a presenter, one block per slot.
+    // The blockhash is not known from the transactions alone and is left empty.
+    pub async fn present<P: Presenter>(
+        &self,
+        owner: &str,
+        limit: usize,
+        presenter: &mut P,
+    ) -> Result<()> {
+        let history = self.get_history(owner, limit).await?;
+        let mut block: Option<Block> = None;
+
+        for entry in history.entries {
+            if block.as_ref().map(|block| block.slot) != Some(entry.slot) {
+                if let Some(block) = block.take() {
+                    presenter.present(&block)?;
+                }
+
+                let mut next = Block::new(entry.slot, String::new());
+                next.update_block_time(entry.block_time);
+                block = Some(next);
+            }
+
+            if let Some(block) = block.as_mut() {
+                block.add_transaction(entry.transaction);
+            }
+        }
+
+        if let Some(block) = block {
+            presenter.present(&block)?;
+        }
+
+        presenter.finish()
+    }
+
+    // Pages through the signatures of the owner and its token accounts, up to
+    // `limit` per address, and replays the hydrated transactions oldest first.
+    pub async fn get_history(&self, owner: &str, limit: usize) -> Result<AccountHistory> {
+        let signatures = self.get_signatures(owner, limit).await?;
+
+        let mut details: Vec<TransactionDetail> = stream::iter(signatures)
+            .map(|signature| {
+                let block_gateway = Arc::clone(&self.block_gateway);
+
+                async move {
+                    let detail = tokio::task::spawn_blocking({
+                        let signature = signature.clone();
+                        move || block_gateway.get_transaction(&signature)
+                    })
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|detail| detail);
+                    (signature, detail)
+                }
+            })
+            .buffered(self.concurrency)
+            .filter_map(|(signature, detail)| async move {
+                match detail {
+                    Ok(detail) => Some(detail),
+                    Err(e) => {
+                        eprintln!("Error getting transaction {}: {}", signature, e);
+                        None
+                    }
+                }
+            })
+            .collect()
+            .await;
+
+        // Signatures come newest first. The sort is stable, so transactions of
+        // the same slot keep the order they were listed in.
+        details.reverse();
+        details.sort_by_key(|detail| detail.slot);
+
+        let mut history = AccountHistory::new(owner);
+
+        for detail in details {
+            for transaction in detail.transactions {
+                if self.mints.contains(&transaction.token) {
+                    history.add_transaction(detail.slot, detail.block_time, transaction);
+                }
+            }
+        }
+
+        Ok(history)
+    }
+
+    async fn get_signatures(&self, owner: &str, limit: usize) -> Result<Vec<String>> {
+        let block_gateway = Arc::clone(&self.block_gateway);
+        let owner = owner.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let mut addresses = vec![owner.clone()];
+            addresses.append(
+                &mut block_gateway
+                    .get_token_accounts(&owner)
+                    .map_err(|e| anyhow!(e))?,
+            );
+
+            let mut lists: Vec<Vec<String>> = Vec::new();
+
+            for address in addresses {
+                let mut signatures: Vec<String> = Vec::new();
+                let mut before: Option<String> = None;
+                let mut count = 0;
+
+                while count < limit {
+                    let page_size = SIGNATURES_PAGE_SIZE.min(limit - count);
+                    let page = block_gateway
+                        .get_signatures_for_address(&address, before.clone(), page_size)
+                        .map_err(|e| anyhow!(e))?;
+
+                    count += page.len();
+                    before = page.last().cloned();
+
+                    let done = page.len() < page_size;
+                    signatures.extend(page);
+
+                    if done {
+                        break;
+                    }
+                }
+
+                lists.push(signatures);
+            }
+
+            Ok(merge_signatures(&lists))
+        })
+        .await?
+    }
+}
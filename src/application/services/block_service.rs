@@ -1,31 +1,29 @@
 use crate::application::gateways::block_gateway::BlockGateway;
+use crate::application::presenters::Presenter;
+use anyhow::Result;
 
-pub struct BlockService<G> {
+pub struct BlockService<G, P> {
     block_gateway: G,
+    presenter: P,
 }
 
-impl<G: BlockGateway> BlockService<G> {
-    pub fn new(block_gateway: G) -> Self {
-        Self { block_gateway }
+impl<G: BlockGateway, P: Presenter> BlockService<G, P> {
+    pub fn new(block_gateway: G, presenter: P) -> Self {
+        Self {
+            block_gateway,
+            presenter,
+        }
     }
 
-    pub fn get_by_id(&self, id: u64) -> () {
+    pub fn get_by_id(&mut self, id: u64) -> Result<()> {
         match self.block_gateway.get_block(id) {
             Ok(block) => {
-                println!("------------------------------------------------------------");
-                println!("Latest block: {:?}", block.slot);
-                for transaction in block.transactions {
-                    println!(
-                        "TX {:?} detected: {:?} sent {:?} USDC to {:?}",
-                        transaction.signature,
-                        transaction.source.address,
-                        transaction.amount(),
-                        transaction.destination.address
-                    );
-                }
+                self.presenter.present(&block)?;
+                self.presenter.finish()
             }
             _ => {
                 println!("Error getting block by id!");
+                Ok(())
             }
         }
     }
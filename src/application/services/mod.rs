@@ -1,2 +1,7 @@
+pub mod account_service;
 pub mod block_service;
+pub mod query_service;
+pub mod range_service;
+pub mod trace_service;
 pub mod track_service;
+pub mod transaction_service;
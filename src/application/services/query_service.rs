@@ -0,0 +1,92 @@
+use crate::application::gateways::block_store::BlockStore;
+use crate::domain::address_book::AddressBook;
+use crate::domain::mint::Mint;
+use anyhow::Result;
+
+// Canned reports over the transfers saved by `track --sink`.
+pub struct QueryService<S> {
+    store: S,
+    address_book: AddressBook,
+}
+
+impl<S: BlockStore> QueryService<S> {
+    pub fn new(store: S, address_book: AddressBook) -> Self {
+        Self {
+            store,
+            address_book,
+        }
+    }
+
+    pub fn top_senders(&self, token: Option<&Mint>, limit: usize) -> Result<()> {
+        println!("------------------------------------------------------------");
+        println!("Top senders");
+        for sender in self.store.top_senders(token, limit)? {
+            println!(
+                "{:?} sent {} {} in {} transfers",
+                self.address_book.label(&sender.address),
+                sender.volume,
+                sender.token.label(),
+                sender.transfers
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn volume_by_hour(&self, token: Option<&Mint>, limit: usize) -> Result<()> {
+        println!("------------------------------------------------------------");
+        println!("Volume by hour (UTC)");
+        for hour in self.store.volume_by_hour(token, limit)? {
+            println!(
+                "[{}] {} {} in {} transfers",
+                format_hour(hour.hour),
+                hour.volume,
+                hour.token.label(),
+                hour.transfers
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn history(&self, address: &str, token: Option<&Mint>, limit: usize) -> Result<()> {
+        println!("------------------------------------------------------------");
+        println!("History of {}", address);
+        for transfer in self.store.history(address, token, limit)? {
+            let token = transfer.token.label();
+
+            if transfer.source == address && transfer.destination == address {
+                println!(
+                    "[{}] SELF {} {} ({})",
+                    transfer.slot, transfer.amount, token, transfer.signature
+                );
+            } else if transfer.destination == address {
+                println!(
+                    "[{}] IN  +{} {} from {:?} ({})",
+                    transfer.slot,
+                    transfer.amount,
+                    token,
+                    self.address_book.label(&transfer.source),
+                    transfer.signature
+                );
+            } else {
+                println!(
+                    "[{}] OUT -{} {} to {:?} ({})",
+                    transfer.slot,
+                    transfer.amount,
+                    token,
+                    self.address_book.label(&transfer.destination),
+                    transfer.signature
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn format_hour(hour: i64) -> String {
+    chrono::DateTime::from_timestamp(hour, 0)
+        .map(|time| time.format("%Y-%m-%d %H:00").to_string())
+        .unwrap_or_else(|| hour.to_string())
+}
@@ -0,0 +1,136 @@
+use crate::application::gateways::block_gateway::BlockGateway;
+use crate::application::presenters::Presenter;
+use crate::domain::slot_tracker::{Backoff, MissedSlot};
+use anyhow::{anyhow, Result};
+use futures_util::stream::{self, StreamExt};
+use std::sync::Arc;
+use std::time::Duration;
+
+const FETCH_BACKOFF: Backoff = Backoff {
+    initial: Duration::from_secs(1),
+    max: Duration::from_secs(8),
+    attempts: 5,
+};
+
+pub struct RangeService<G, P> {
+    block_gateway: Arc<G>,
+    presenter: P,
+    concurrency: usize,
+}
+
+impl<G: BlockGateway + Send + Sync + 'static, P: Presenter> RangeService<G, P> {
+    pub fn new(block_gateway: G, presenter: P, concurrency: usize) -> Self {
+        Self {
+            block_gateway: Arc::new(block_gateway),
+            presenter,
+            concurrency: concurrency.max(1),
+        }
+    }
+
+    pub async fn range(&mut self, from_slot: u64, to_slot: u64) -> Result<()> {
+        let block_gateway = Arc::clone(&self.block_gateway);
+        let slots =
+            tokio::task::spawn_blocking(move || block_gateway.get_blocks(from_slot, to_slot))
+                .await?
+                .map_err(|e| anyhow!(e))?;
+
+        // Blocks are fetched concurrently, `buffered` keeps them in slot order.
+        // Failed fetches are retried with a backoff before the slot is given up on.
+        let mut blocks = stream::iter(slots)
+            .map(|slot| {
+                let block_gateway = Arc::clone(&self.block_gateway);
+
+                async move {
+                    let mut attempts = 0;
+                    loop {
+                        let block_gateway = Arc::clone(&block_gateway);
+                        let block =
+                            tokio::task::spawn_blocking(move || block_gateway.get_block(slot))
+                                .await
+                                .map_err(|e| e.to_string())
+                                .and_then(|block| block);
+                        attempts += 1;
+
+                        match block {
+                            Err(_) if attempts < FETCH_BACKOFF.attempts => {
+                                tokio::time::sleep(FETCH_BACKOFF.delay(attempts)).await
+                            }
+                            block => return (slot, attempts, block),
+                        }
+                    }
+                }
+            })
+            .buffered(self.concurrency);
+
+        let mut missed = Vec::new();
+        while let Some((slot, attempts, block)) = blocks.next().await {
+            match block {
+                Ok(block) => self.presenter.present(&block)?,
+                Err(error) => {
+                    let slot = MissedSlot {
+                        slot,
+                        attempts,
+                        error,
+                    };
+                    self.presenter.missed(&slot)?;
+                    missed.push(slot.slot);
+                }
+            }
+        }
+
+        self.presenter.finish()?;
+
+        if missed.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "gave up on {} slots: {}",
+                missed.len(),
+                missed
+                    .iter()
+                    .map(|slot| slot.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+        }
+    }
+
+    // Finds the first slot whose block time is at or after the given timestamp.
+    pub async fn resolve_slot(&self, timestamp: i64) -> Result<u64> {
+        let block_gateway = Arc::clone(&self.block_gateway);
+
+        tokio::task::spawn_blocking(move || {
+            let mut low = block_gateway
+                .get_first_available_block()
+                .map_err(|e| anyhow!(e))?;
+            let mut high = block_gateway.get_slot().map_err(|e| anyhow!(e))?;
+
+            while low < high {
+                let middle = low + (high - low) / 2;
+                let block = block_gateway
+                    .get_blocks_with_limit(middle, 1)
+                    .map_err(|e| anyhow!(e))?
+                    .first()
+                    .copied();
+
+                match block {
+                    Some(block) if block <= high => {
+                        let block_time = block_gateway
+                            .get_block_time(block)
+                            .map_err(|e| anyhow!(e))?;
+
+                        if block_time < timestamp {
+                            low = block + 1;
+                        } else {
+                            high = middle;
+                        }
+                    }
+                    _ => high = middle,
+                }
+            }
+
+            Ok(low)
+        })
+        .await?
+    }
+}
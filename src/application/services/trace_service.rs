@@ -0,0 +1,259 @@
+use crate::application::gateways::block_gateway::BlockGateway;
+use crate::application::presenters::Presenter;
+use crate::application::services::account_service::AccountService;
+use crate::domain::account_history::{AccountHistory, Direction, HistoryEntry};
+use crate::domain::address_book::AddressBook;
+use crate::domain::amount::Amount;
+use crate::domain::block::Block;
+use crate::domain::mint::Mint;
+use crate::domain::trace::{attenuate, StopReason, TraceDirection, TraceNode};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+struct Hop {
+    path: Vec<usize>,
+    budget: Option<Amount>,
+    ancestors: Vec<String>,
+}
+
+pub struct TraceService<G> {
+    account_service: AccountService<G>,
+    stops: HashSet<String>,
+    address_book: AddressBook,
+}
+
+impl<G: BlockGateway + Send + Sync + 'static> TraceService<G> {
+    pub fn new(
+        block_gateway: G,
+        mints: Vec<Mint>,
+        concurrency: usize,
+        stops: HashSet<String>,
+        address_book: AddressBook,
+    ) -> Self {
+        Self {
+            account_service: AccountService::new(
+                block_gateway,
+                mints,
+                concurrency,
+                address_book.clone(),
+            ),
+            stops,
+            address_book,
+        }
+    }
+
+    // Follows the funds of an address hop by hop, breadth first. Every hop only
+    // considers transfers on the right side of the slot the funds arrived at
+    // and carries at most the amount that reached it, see `attenuate`. At most
+    // `branches` of the largest transfers are followed from each address.
+    pub async fn trace(
+        &self,
+        address: &str,
+        slot: Option<u64>,
+        direction: TraceDirection,
+        hops: usize,
+        limit: usize,
+        branches: usize,
+    ) -> Result<TraceNode> {
+        let slot = slot.unwrap_or(match direction {
+            TraceDirection::Downstream => 0,
+            TraceDirection::Upstream => u64::MAX,
+        });
+        let mut root = TraceNode::new(address, slot, None);
+        let mut histories: HashMap<String, AccountHistory> = HashMap::new();
+        let mut queue = VecDeque::from([Hop {
+            path: Vec::new(),
+            budget: None,
+            ancestors: vec![address.to_string()],
+        }]);
+
+        if self.stops.contains(address) {
+            root.update_stop(StopReason::Known);
+            return Ok(root);
+        }
+
+        while let Some(hop) = queue.pop_front() {
+            if hop.path.len() >= hops {
+                continue;
+            }
+
+            let node = locate(&mut root, &hop.path);
+            if !histories.contains_key(&node.address) {
+                match self.account_service.get_history(&node.address, limit).await {
+                    Ok(history) => {
+                        histories.insert(node.address.clone(), history);
+                    }
+                    Err(e) => {
+                        node.update_stop(StopReason::Unavailable(e.to_string()));
+                        continue;
+                    }
+                }
+            }
+            let history = &histories[&node.address];
+
+            let mut entries: Vec<&HistoryEntry> = history
+                .entries
+                .iter()
+                .filter(|entry| match direction {
+                    TraceDirection::Downstream => {
+                        entry.direction == Direction::Outgoing && entry.slot >= node.slot
+                    }
+                    TraceDirection::Upstream => {
+                        entry.direction == Direction::Incoming && entry.slot <= node.slot
+                    }
+                })
+                .filter(|entry| match &node.transaction {
+                    Some(transaction) => entry.transaction.token == transaction.token,
+                    None => true,
+                })
+                .collect();
+            // Funds leave after they arrived and arrived before they left.
+            if direction == TraceDirection::Upstream {
+                entries.reverse();
+            }
+
+            let amounts: Vec<Amount> = entries
+                .iter()
+                .map(|entry| entry.transaction.amount())
+                .collect();
+            let mut followed: Vec<(&HistoryEntry, Amount)> = entries
+                .into_iter()
+                .zip(attenuate(hop.budget, &amounts))
+                .filter(|(_, amount)| !amount.is_zero())
+                .collect();
+            if followed.len() > branches {
+                let mut largest: Vec<usize> = (0..followed.len()).collect();
+                largest.sort_by_key(|index| std::cmp::Reverse(followed[*index].1.raw));
+                let largest: HashSet<usize> = largest.into_iter().take(branches).collect();
+
+                followed = followed
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(index, followed)| largest.contains(&index).then_some(followed))
+                    .collect();
+            }
+
+            for (entry, amount) in followed {
+                let mut transaction = entry.transaction.clone();
+                transaction.amount = amount;
+                let counterparty = match direction {
+                    TraceDirection::Downstream => transaction.destination.address.clone(),
+                    TraceDirection::Upstream => transaction.source.address.clone(),
+                };
+
+                let mut child = TraceNode::new(&counterparty, entry.slot, Some(transaction));
+                if self.stops.contains(&counterparty) {
+                    child.update_stop(StopReason::Known);
+                } else if hop.ancestors.contains(&counterparty) {
+                    child.update_stop(StopReason::Cycle);
+                } else {
+                    let mut path = hop.path.clone();
+                    path.push(node.children.len());
+                    let mut ancestors = hop.ancestors.clone();
+                    ancestors.push(counterparty);
+
+                    queue.push_back(Hop {
+                        path,
+                        budget: Some(amount),
+                        ancestors,
+                    });
+                }
+
+                node.add_child(child);
+            }
+        }
+
+        Ok(root)
+    }
+
+    pub fn print(&self, root: &TraceNode, direction: TraceDirection) {
+        println!("------------------------------------------------------------");
+        match direction {
+            TraceDirection::Downstream => println!("Funds sent by {}", root.address),
+            TraceDirection::Upstream => println!("Funds received by {}", root.address),
+        }
+        if let Some(label) = self.address_book.get(&root.address) {
+            println!("  ({})", label);
+        }
+        if let Some(stop) = &root.stop {
+            println!("  (stop: {})", describe_stop(stop));
+        }
+
+        for child in &root.children {
+            self.print_node(child, direction, 1);
+        }
+    }
+
+    // Hands the attenuated transfers of the trace to a presenter, one block per slot.
+    pub fn present<P: Presenter>(&self, root: &TraceNode, presenter: &mut P) -> Result<()> {
+        let mut transactions = root.transactions();
+        transactions.sort_by_key(|(slot, _)| *slot);
+
+        let mut block: Option<Block> = None;
+        for (slot, transaction) in transactions {
+            if block.as_ref().map(|block| block.slot) != Some(slot) {
+                if let Some(block) = block.take() {
+                    presenter.present(&block)?;
+                }
+                block = Some(Block::new(slot, String::new()));
+            }
+
+            if let Some(block) = block.as_mut() {
+                block.add_transaction(transaction.clone());
+            }
+        }
+
+        if let Some(block) = block {
+            presenter.present(&block)?;
+        }
+
+        presenter.finish()
+    }
+
+    fn print_node(&self, node: &TraceNode, direction: TraceDirection, depth: usize) {
+        let indent = "  ".repeat(depth);
+        let Some(transaction) = &node.transaction else {
+            return;
+        };
+        let stop = match &node.stop {
+            Some(stop) => format!(" (stop: {})", describe_stop(stop)),
+            None => String::new(),
+        };
+
+        println!(
+            "{}{} {} {} {} {} [slot {}] ({}){}",
+            indent,
+            match direction {
+                TraceDirection::Downstream => "->",
+                TraceDirection::Upstream => "<-",
+            },
+            transaction.amount(),
+            transaction.token.label(),
+            match direction {
+                TraceDirection::Downstream => "to",
+                TraceDirection::Upstream => "from",
+            },
+            self.address_book.label(&node.address),
+            node.slot,
+            transaction.signature,
+            stop
+        );
+
+        for child in &node.children {
+            self.print_node(child, direction, depth + 1);
+        }
+    }
+}
+
+fn describe_stop(stop: &StopReason) -> String {
+    match stop {
+        StopReason::Known => "known address".to_string(),
+        StopReason::Cycle => "already traced in this branch".to_string(),
+        StopReason::Unavailable(error) => format!("history unavailable, {}", error),
+    }
+}
+
+fn locate<'a>(root: &'a mut TraceNode, path: &[usize]) -> &'a mut TraceNode {
+    path.iter()
+        .fold(root, |node, index| &mut node.children[*index])
+}
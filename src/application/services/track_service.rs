@@ -1,54 +1,119 @@
-use crate::application::gateways::block_gateway::BlockGateway;
-use crate::domain::block::Block;
-use anyhow::Result;
+use crate::application::gateways::block_gateway::{BlockGateway, BlockUpdate, Unsubscribe};
+use crate::application::presenters::Presenter;
+use crate::domain::filter::TransferFilter;
+use anyhow::{anyhow, Result};
 use std::sync::Arc;
 use tokio::io::AsyncReadExt;
-use tokio::sync::mpsc::unbounded_channel;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
 
-pub struct TrackService<G> {
+pub struct Subscription {
+    block_update_receiver: UnboundedReceiver<BlockUpdate>,
+    unsubscribe_receiver: UnboundedReceiver<Unsubscribe>,
+    filter: TransferFilter,
+}
+
+impl Subscription {
+    // Next update, blocks holding only the transfers that pass the filter.
+    pub async fn recv(&mut self) -> Option<BlockUpdate> {
+        let mut block_update = self.block_update_receiver.recv().await?;
+        if let BlockUpdate::Block(block) = &mut block_update {
+            self.filter.apply(block);
+        }
+
+        Some(block_update)
+    }
+
+    pub async fn unsubscribe(mut self) {
+        while let Some(unsubscribe) = self.unsubscribe_receiver.recv().await {
+            unsubscribe().await
+        }
+    }
+}
+
+pub struct TrackService<G, P> {
     block_gateway: G,
+    presenter: P,
+    filter: TransferFilter,
+    start_slot: Option<u64>,
 }
 
-impl<G: BlockGateway + Clone + Send + Sync + 'static> TrackService<G> {
-    pub fn new(block_gateway: G) -> Self {
-        Self { block_gateway }
+impl<G: BlockGateway + Clone + Send + Sync + 'static, P: Presenter> TrackService<G, P> {
+    // With a start slot, e.g. the one after a checkpoint, the blocks missed
+    // since then are presented before the live ones.
+    pub fn new(
+        block_gateway: G,
+        presenter: P,
+        filter: TransferFilter,
+        start_slot: Option<u64>,
+    ) -> Self {
+        Self {
+            block_gateway,
+            presenter,
+            filter,
+            start_slot,
+        }
+    }
+
+    #[allow(clippy::needless_return)]
+    pub async fn track(&mut self) -> Result<()> {
+        let mut subscription = self.subscribe().await?;
+
+        while let Some(block_update) = subscription.recv().await {
+            self.present(&block_update)?;
+        }
+        self.finish()?;
+
+        tokio::io::stdin().read_u8().await?;
+
+        subscription.unsubscribe().await;
+
+        return Ok(());
+    }
+
+    // Hands an update to the presenter, for callers that consume the
+    // subscription themselves and still want the sinks and alerts.
+    pub fn present(&mut self, block_update: &BlockUpdate) -> Result<()> {
+        match block_update {
+            BlockUpdate::Block(block) => self.presenter.present(block),
+            BlockUpdate::Missed(slot) => self.presenter.missed(slot),
+            BlockUpdate::Connection(state) => self.presenter.connection(state),
+            BlockUpdate::Finality(update) => self.presenter.finality(update),
+        }
+    }
+
+    pub fn finish(&mut self) -> Result<()> {
+        self.presenter.finish()
     }
 
-    pub async fn track(&self) -> Result<()> {
-        let (ready_sender, mut ready_receiver) = unbounded_channel::<()>();
-        let (unsubscribe_sender, mut unsubscribe_receiver) = unbounded_channel::<_>();
-        let (block_update_sender, mut block_update_receiver) = unbounded_channel::<Block>();
+    // Starts the slot subscription and waits until it is ready, for callers
+    // that consume the block updates themselves.
+    pub async fn subscribe(&self) -> Result<Subscription> {
+        let (ready_sender, mut ready_receiver) = unbounded_channel::<Result<(), String>>();
+        let (unsubscribe_sender, unsubscribe_receiver) = unbounded_channel::<_>();
+        let (block_update_sender, block_update_receiver) = unbounded_channel::<BlockUpdate>();
         let block_gateway = Arc::new(self.block_gateway.clone());
 
         block_gateway
-            .subscribe(&ready_sender, &unsubscribe_sender, &block_update_sender)
+            .subscribe(
+                self.start_slot,
+                &ready_sender,
+                &unsubscribe_sender,
+                &block_update_sender,
+            )
             .await?;
 
         drop(ready_sender);
         drop(unsubscribe_sender);
         drop(block_update_sender);
 
-        while let Some(_) = ready_receiver.recv().await {}
-
-        while let Some(block) = block_update_receiver.recv().await {
-            println!("------------------------------------------------------------");
-            println!("Latest block: {:?}", block.slot);
-            for transaction in block.transactions {
-                println!(
-                    "TX detected: {:?} sent {:?} USDC to {:?}",
-                    transaction.source.address,
-                    transaction.amount(),
-                    transaction.destination.address
-                );
-            }
-        }
-
-        tokio::io::stdin().read_u8().await?;
-
-        while let Some(unsubscribe) = unsubscribe_receiver.recv().await {
-            unsubscribe().await
+        while let Some(ready) = ready_receiver.recv().await {
+            ready.map_err(|e| anyhow!(e))?;
         }
 
-        return Ok(());
+        Ok(Subscription {
+            block_update_receiver,
+            unsubscribe_receiver,
+            filter: self.filter.clone(),
+        })
     }
 }
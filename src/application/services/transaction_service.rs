@@ -0,0 +1,97 @@
+use crate::application::gateways::block_gateway::BlockGateway;
+use crate::domain::address_book::AddressBook;
+use crate::domain::mint::{Mint, NATIVE_MINT};
+use crate::domain::transaction_detail::Instruction;
+
+pub struct TransactionService<G> {
+    block_gateway: G,
+    address_book: AddressBook,
+}
+
+impl<G: BlockGateway> TransactionService<G> {
+    pub fn new(block_gateway: G, address_book: AddressBook) -> Self {
+        Self {
+            block_gateway,
+            address_book,
+        }
+    }
+
+    pub fn get_by_signature(&self, signature: &str) {
+        match self.block_gateway.get_transaction(signature) {
+            Ok(detail) => {
+                let native = Mint::new(NATIVE_MINT);
+
+                println!("------------------------------------------------------------");
+                println!("Transaction: {}", detail.signature);
+                println!("Slot: {:?}", detail.slot);
+                if let Some(block_time) = detail.block_time {
+                    println!("Block time: {}", block_time);
+                }
+                match &detail.error {
+                    Some(error) => println!("Status: failed ({})", error),
+                    None => println!("Status: success"),
+                }
+                println!("Fee payer: {}", detail.fee_payer);
+                println!("Fee: {} {}", detail.fee, native.label());
+                if let Some(compute_units) = detail.compute_units {
+                    println!("Compute units: {}", compute_units);
+                }
+
+                println!("Movements:");
+                for transaction in &detail.transactions {
+                    println!(
+                        "  {:?} sent {} {} to {:?}",
+                        self.address_book.label(&transaction.source.address),
+                        transaction.amount(),
+                        transaction.token.label(),
+                        self.address_book.label(&transaction.destination.address)
+                    );
+                    if let Some(fee) = transaction.fee {
+                        println!("      withheld fee: {} {}", fee, transaction.token.label());
+                    }
+                }
+
+                println!("Instructions:");
+                for (position, instruction) in detail.instructions.iter().enumerate() {
+                    self.print_instruction(&format!("#{}", position + 1), instruction, 1);
+                }
+
+                println!("Logs:");
+                for log in &detail.logs {
+                    println!("  {}", log);
+                }
+            }
+            Err(e) => {
+                println!("Error getting transaction by signature: {}", e);
+            }
+        }
+    }
+
+    fn print_instruction(&self, position: &str, instruction: &Instruction, depth: usize) {
+        let indent = "  ".repeat(depth);
+        let program = match self.address_book.get(&instruction.program) {
+            Some(label) => format!("{} [{}]", instruction.program, label),
+            None => instruction.program.clone(),
+        };
+
+        match &instruction.event {
+            Some(event) => println!("{}{} {} {:?}", indent, position, program, event),
+            None => println!(
+                "{}{} {} ({} accounts)",
+                indent,
+                position,
+                program,
+                instruction.accounts.len()
+            ),
+        }
+
+        for (inner_position, inner_instruction) in instruction.inner_instructions.iter().enumerate()
+        {
+            self.print_instruction(
+                &format!("{}.{}", position, inner_position + 1),
+                inner_instruction,
+                depth + 1,
+            );
+        }
+    }
+}
@@ -1,4 +1,13 @@
-use clap::{Parser, Subcommand};
+use crate::application::presenters::dot_presenter::GraphOptions;
+use crate::domain::commitment::Commitment;
+use crate::domain::filter::{Threshold, TransferFilter};
+use crate::domain::mint::Mint;
+use crate::infrastructure::cluster::{Cluster, Endpoints};
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
 
 #[derive(Parser)]
 #[command(name = "Solana Transaction Tracker")]
@@ -6,6 +15,155 @@ use clap::{Parser, Subcommand};
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+    #[arg(
+        long,
+        global = true,
+        help = "TOML or JSON file naming addresses, on top of the built-in labels"
+    )]
+    pub labels: Option<PathBuf>,
+    #[arg(
+        long,
+        global = true,
+        help = "TOML or JSON configuration file, e.g. with a [filter] section"
+    )]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct ConnectionArgs {
+    #[arg(
+        short,
+        long,
+        default_value = "mainnet-beta",
+        help = "Cluster moniker: mainnet-beta, devnet, testnet or localnet"
+    )]
+    pub cluster: Cluster,
+    #[arg(long, help = "JSON RPC URL, overrides the cluster moniker")]
+    pub rpc_url: Option<String>,
+    #[arg(long, help = "WebSocket URL, derived from --rpc-url when omitted")]
+    pub ws_url: Option<String>,
+}
+
+impl ConnectionArgs {
+    pub fn endpoints(&self) -> Endpoints {
+        Endpoints::resolve(self.cluster, self.rpc_url.clone(), self.ws_url.clone())
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+    Jsonl,
+    Csv,
+    Graph,
+    Html,
+    Sankey,
+}
+
+// `track` never finishes on its own, so it only takes the formats written
+// block by block.
+fn track_format() -> impl TypedValueParser<Value = Format> {
+    PossibleValuesParser::new(["text", "json", "jsonl", "csv"])
+        .map(|value| Format::from_str(&value, true).expect("a possible value"))
+}
+
+// Where `track` keeps the blocks besides printing them, `sqlite:path.db`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sink {
+    Sqlite(PathBuf),
+}
+
+impl FromStr for Sink {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once(':') {
+            Some(("sqlite", path)) if !path.is_empty() => Ok(Sink::Sqlite(PathBuf::from(path))),
+            _ => Err(format!("unknown sink {:?}, expected sqlite:<path>", value)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Report {
+    TopSenders,
+    VolumeByHour,
+    History,
+}
+
+#[derive(Args, Default)]
+pub struct GraphArgs {
+    #[arg(
+        long,
+        help = "With --format graph, merge parallel transfers into one edge"
+    )]
+    pub merge_edges: bool,
+    #[arg(
+        long,
+        default_value = "0",
+        help = "With --format graph or sankey, drop flows below this amount"
+    )]
+    pub dust: f64,
+    #[arg(
+        long,
+        help = "With --format graph or sankey, keep only the N addresses with the most volume"
+    )]
+    pub top: Option<usize>,
+}
+
+impl GraphArgs {
+    pub fn options(&self) -> GraphOptions {
+        GraphOptions {
+            merge_parallel: self.merge_edges,
+            dust: self.dust,
+            top: self.top,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct FilterArgs {
+    #[arg(
+        long = "source",
+        help = "Only keep transfers sent by this owner, can be repeated"
+    )]
+    pub sources: Vec<String>,
+    #[arg(
+        long = "destination",
+        help = "Only keep transfers received by this owner, can be repeated"
+    )]
+    pub destinations: Vec<String>,
+    #[arg(
+        long = "involves",
+        help = "Only keep transfers sent or received by this owner, can be repeated"
+    )]
+    pub involves: Vec<String>,
+    #[arg(
+        long = "program",
+        help = "Only keep transfers made through this program, can be repeated"
+    )]
+    pub programs: Vec<String>,
+    #[arg(long, help = "Only keep transfers of at least this many tokens")]
+    pub min_amount: Option<Threshold>,
+    #[arg(long, help = "Only keep transfers of at most this many tokens")]
+    pub max_amount: Option<Threshold>,
+}
+
+impl FilterArgs {
+    // Flags add to the address sets of the configured filter and replace its bounds.
+    pub fn filter(&self, mut filter: TransferFilter) -> TransferFilter {
+        filter.sources.extend(self.sources.iter().cloned());
+        filter
+            .destinations
+            .extend(self.destinations.iter().cloned());
+        filter.involves.extend(self.involves.iter().cloned());
+        filter.programs.extend(self.programs.iter().cloned());
+        filter.min_amount = self.min_amount.or(filter.min_amount);
+        filter.max_amount = self.max_amount.or(filter.max_amount);
+
+        filter
+    }
 }
 
 #[derive(Subcommand)]
@@ -13,13 +171,298 @@ pub enum Commands {
     #[command(name = "track")]
     #[command(about = "Track transactions for a given cluster")]
     Track {
-        #[arg(short, long, default_value = "mainnet-beta")]
-        cluster: String,
+        #[command(flatten)]
+        connection: ConnectionArgs,
+        #[arg(
+            long = "mint",
+            default_value = "USDC",
+            help = "Mint symbol or address to track, can be repeated"
+        )]
+        mints: Vec<Mint>,
+        #[arg(
+            long,
+            value_parser = track_format(),
+            default_value = "text",
+            help = "Output format"
+        )]
+        format: Format,
+        #[command(flatten)]
+        filter: FilterArgs,
+        #[arg(
+            long,
+            help = "Show an interactive dashboard instead of printing blocks"
+        )]
+        tui: bool,
+        #[arg(
+            long,
+            default_value = "300",
+            help = "Rolling window in seconds for the dashboard top senders and receivers"
+        )]
+        window: u64,
+        #[arg(
+            long,
+            conflicts_with = "tui",
+            help = "Also report transfer statistics over 1m, 5m and 1h windows"
+        )]
+        stats: bool,
+        #[arg(
+            long,
+            default_value = "60",
+            help = "Seconds between two statistics reports"
+        )]
+        stats_interval: u64,
+        #[arg(
+            long = "sink",
+            help = "Also save the blocks, e.g. to sqlite:transfers.db, can be repeated"
+        )]
+        sinks: Vec<Sink>,
+        #[arg(
+            long,
+            help = "File keeping the last processed slot, tracking resumes after it"
+        )]
+        checkpoint: Option<PathBuf>,
+        #[arg(
+            long,
+            default_value = "30",
+            help = "Seconds without a slot update before reconnecting"
+        )]
+        stall_timeout: u64,
+        #[arg(
+            long,
+            default_value = "finalized",
+            help = "Commitment of the blocks output: confirmed or finalized; processed gives confirmed blocks, RPC nodes serve none below that"
+        )]
+        commitment: Commitment,
     },
     Block {
-        #[arg(short, long, default_value = "mainnet-beta")]
-        cluster: String,
+        #[command(flatten)]
+        connection: ConnectionArgs,
+        #[arg(
+            long = "mint",
+            default_value = "USDC",
+            help = "Mint symbol or address to track, can be repeated"
+        )]
+        mints: Vec<Mint>,
+        #[arg(long, value_enum, default_value = "text", help = "Output format")]
+        format: Format,
+        #[command(flatten)]
+        graph: GraphArgs,
         #[arg(short, long)]
         block_id: u64,
     },
+    #[command(name = "range")]
+    #[command(about = "Backfill transactions for a range of slots or times")]
+    Range {
+        #[command(flatten)]
+        connection: ConnectionArgs,
+        #[arg(
+            long = "mint",
+            default_value = "USDC",
+            help = "Mint symbol or address to track, can be repeated"
+        )]
+        mints: Vec<Mint>,
+        #[arg(long, value_enum, default_value = "text", help = "Output format")]
+        format: Format,
+        #[command(flatten)]
+        graph: GraphArgs,
+        #[arg(
+            long,
+            required_unless_present = "from_time",
+            conflicts_with = "from_time"
+        )]
+        from: Option<u64>,
+        #[arg(
+            long,
+            required_unless_present = "to_time",
+            conflicts_with = "to_time",
+            help = "Last slot of the range, inclusive"
+        )]
+        to: Option<u64>,
+        #[arg(long, value_parser = parse_timestamp, help = "Unix timestamp or RFC 3339 date")]
+        from_time: Option<i64>,
+        #[arg(long, value_parser = parse_timestamp, help = "Unix timestamp or RFC 3339 date")]
+        to_time: Option<i64>,
+        #[arg(
+            long,
+            default_value = "8",
+            help = "Number of blocks fetched concurrently"
+        )]
+        concurrency: usize,
+    },
+    #[command(name = "account")]
+    #[command(about = "Show the transfer history of a wallet")]
+    Account {
+        #[command(flatten)]
+        connection: ConnectionArgs,
+        #[arg(
+            long = "mint",
+            default_value = "USDC",
+            help = "Mint symbol or address to track, can be repeated"
+        )]
+        mints: Vec<Mint>,
+        #[arg(long, value_enum, default_value = "text", help = "Output format")]
+        format: Format,
+        #[command(flatten)]
+        graph: GraphArgs,
+        #[arg(short, long, help = "Owner wallet address")]
+        address: String,
+        #[arg(
+            long,
+            default_value = "1000",
+            help = "Maximum number of signatures fetched per address"
+        )]
+        limit: usize,
+        #[arg(
+            long,
+            default_value = "8",
+            help = "Number of transactions fetched concurrently"
+        )]
+        concurrency: usize,
+    },
+    #[command(name = "trace")]
+    #[command(about = "Follow funds from a wallet over several hops")]
+    Trace {
+        #[command(flatten)]
+        connection: ConnectionArgs,
+        #[arg(
+            long = "mint",
+            default_value = "USDC",
+            help = "Mint symbol or address to track, can be repeated"
+        )]
+        mints: Vec<Mint>,
+        #[arg(long, value_enum, default_value = "text", help = "Output format")]
+        format: Format,
+        #[command(flatten)]
+        graph: GraphArgs,
+        #[arg(short, long, help = "Wallet address the trace starts from")]
+        address: String,
+        #[arg(
+            long,
+            help = "Only follow transfers from this slot on, or up to it with --upstream"
+        )]
+        slot: Option<u64>,
+        #[arg(
+            long,
+            help = "Follow where the funds came from instead of where they went"
+        )]
+        upstream: bool,
+        #[arg(long, default_value = "3", help = "Maximum number of hops")]
+        hops: usize,
+        #[arg(
+            long,
+            default_value = "10",
+            help = "Maximum number of transfers followed from each address, largest first"
+        )]
+        branches: usize,
+        #[arg(
+            long = "stop",
+            help = "Address the trace does not go past, can be repeated"
+        )]
+        stops: Vec<String>,
+        #[arg(
+            long,
+            default_value = "200",
+            help = "Maximum number of signatures fetched per address"
+        )]
+        limit: usize,
+        #[arg(
+            long,
+            default_value = "8",
+            help = "Number of transactions fetched concurrently"
+        )]
+        concurrency: usize,
+    },
+    #[command(name = "serve")]
+    #[command(about = "Serve a live transfer graph and table in the browser")]
+    Serve {
+        #[command(flatten)]
+        connection: ConnectionArgs,
+        #[arg(
+            long = "mint",
+            default_value = "USDC",
+            help = "Mint symbol or address to track, can be repeated"
+        )]
+        mints: Vec<Mint>,
+        #[command(flatten)]
+        filter: FilterArgs,
+        #[arg(
+            long,
+            default_value = "127.0.0.1:8080",
+            help = "Address the HTTP server listens on"
+        )]
+        bind: SocketAddr,
+        #[arg(
+            long,
+            default_value = "30",
+            help = "Seconds without a slot update before reconnecting"
+        )]
+        stall_timeout: u64,
+        #[arg(
+            long,
+            default_value = "finalized",
+            help = "Commitment of the blocks output: confirmed or finalized; processed gives confirmed blocks, RPC nodes serve none below that"
+        )]
+        commitment: Commitment,
+    },
+    #[command(name = "query")]
+    #[command(about = "Report on the transfers saved by track --sink")]
+    Query {
+        #[arg(long, help = "SQLite database written by track --sink sqlite:<path>")]
+        db: PathBuf,
+        #[arg(value_enum)]
+        report: Report,
+        #[arg(
+            short,
+            long,
+            required_if_eq("report", "history"),
+            help = "Owner wallet address, for the history report"
+        )]
+        address: Option<String>,
+        #[arg(long = "mint", help = "Only report on this mint symbol or address")]
+        mint: Option<Mint>,
+        #[arg(
+            long,
+            default_value = "20",
+            help = "Number of senders per mint, hours or transfers reported"
+        )]
+        limit: usize,
+    },
+    #[command(name = "tx")]
+    #[command(about = "Inspect every movement and instruction of a single transaction")]
+    Tx {
+        #[command(flatten)]
+        connection: ConnectionArgs,
+        #[arg(short, long)]
+        signature: String,
+    },
+}
+
+fn parse_timestamp(value: &str) -> Result<i64, String> {
+    if let Ok(timestamp) = value.parse::<i64>() {
+        return Ok(timestamp);
+    }
+
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|date| date.timestamp())
+        .map_err(|e| {
+            format!(
+                "{:?} is neither a unix timestamp nor an RFC 3339 date: {}",
+                value, e
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sink() {
+        assert_eq!(
+            "sqlite:transfers.db".parse::<Sink>(),
+            Ok(Sink::Sqlite(PathBuf::from("transfers.db")))
+        );
+        assert!("sqlite:".parse::<Sink>().is_err());
+        assert!("postgres://localhost".parse::<Sink>().is_err());
+    }
 }
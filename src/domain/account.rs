@@ -1,13 +1,16 @@
-#[derive(Debug, Clone)]
+use crate::domain::amount::Amount;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
 pub struct Account {
     pub address: String,
     pub index: u8,
-    pub pre_balance: f64,
-    pub post_balance: f64,
+    pub pre_balance: Amount,
+    pub post_balance: Amount,
 }
 
 impl Account {
-    pub fn new<U: ToString>(address: U, index: u8, pre_balance: f64) -> Self {
+    pub fn new<U: ToString>(address: U, index: u8, pre_balance: Amount) -> Self {
         Self {
             address: address.to_string(),
             index,
@@ -16,7 +19,7 @@ impl Account {
         }
     }
 
-    pub fn update_post_balance(&mut self, post_balance: f64) {
+    pub fn update_post_balance(&mut self, post_balance: Amount) {
         self.post_balance = post_balance;
     }
 }
@@ -29,10 +32,10 @@ mod tests {
     fn test_update_post_balance() {
         let address = "test_address";
         let index = 1;
-        let pre_balance = 100.0;
+        let pre_balance = Amount::new(100_000_000, 6);
         let mut account = Account::new(address, index, pre_balance);
 
-        account.update_post_balance(150.0);
-        assert_eq!(account.post_balance, 150.0);
+        account.update_post_balance(Amount::new(150_000_000, 6));
+        assert_eq!(account.post_balance, Amount::new(150_000_000, 6));
     }
 }
@@ -0,0 +1,203 @@
+use crate::domain::amount::Amount;
+use crate::domain::transaction::Transaction;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Incoming,
+    Outgoing,
+    Internal,
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub direction: Direction,
+    pub transaction: Transaction,
+    pub balance: Amount,
+}
+
+#[derive(Debug, Clone)]
+pub struct AccountHistory {
+    pub owner: String,
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl AccountHistory {
+    pub fn new<U: ToString>(owner: U) -> Self {
+        Self {
+            owner: owner.to_string(),
+            entries: Vec::new(),
+        }
+    }
+
+    // Entries must be added oldest first. The balance of the owner's account is
+    // carried across transfers of the same transaction, so several movements in
+    // one transaction each get their own intermediate balance.
+    pub fn add_transaction(
+        &mut self,
+        slot: u64,
+        block_time: Option<i64>,
+        transaction: Transaction,
+    ) {
+        let is_source = transaction.source.address == self.owner;
+        let is_destination = transaction.destination.address == self.owner;
+
+        let (direction, account) = match (is_source, is_destination) {
+            (true, true) => (Direction::Internal, &transaction.source),
+            (true, false) => (Direction::Outgoing, &transaction.source),
+            (false, true) => (Direction::Incoming, &transaction.destination),
+            (false, false) => return,
+        };
+
+        let previous = self.entries.iter().rev().find(|entry| {
+            entry.transaction.signature == transaction.signature
+                && entry.transaction.token == transaction.token
+                && owner_account_index(entry) == account.index
+        });
+        let starting_balance = match previous {
+            Some(entry) => entry.balance,
+            None => account.pre_balance,
+        };
+
+        let balance = match direction {
+            Direction::Incoming => starting_balance
+                .checked_add(transaction.amount())
+                .unwrap_or(account.post_balance),
+            Direction::Outgoing => starting_balance.saturating_sub(transaction.amount()),
+            Direction::Internal => starting_balance,
+        };
+
+        self.entries.push(HistoryEntry {
+            slot,
+            block_time,
+            direction,
+            transaction,
+            balance,
+        });
+    }
+}
+
+fn owner_account_index(entry: &HistoryEntry) -> u8 {
+    match entry.direction {
+        Direction::Incoming => entry.transaction.destination.index,
+        _ => entry.transaction.source.index,
+    }
+}
+
+// Merges the signatures of several addresses, each newest first as the RPC
+// lists them, into one list that keeps the order of every address. A
+// transaction listed for more than one address waits until it is next in all
+// of them.
+pub fn merge_signatures(lists: &[Vec<String>]) -> Vec<String> {
+    let mut remaining: HashMap<&String, usize> = HashMap::new();
+    for signature in lists.iter().flatten() {
+        *remaining.entry(signature).or_default() += 1;
+    }
+
+    let mut positions = vec![0; lists.len()];
+    let mut merged: Vec<String> = Vec::new();
+
+    loop {
+        let heads: Vec<&String> = lists
+            .iter()
+            .zip(positions.iter_mut())
+            .filter_map(|(list, position)| {
+                // Signatures listed twice were merged already.
+                while list
+                    .get(*position)
+                    .is_some_and(|signature| !remaining.contains_key(signature))
+                {
+                    *position += 1;
+                }
+                list.get(*position)
+            })
+            .collect();
+        let ready = heads.iter().find(|head| {
+            let at_head = heads.iter().filter(|other| other == head).count();
+            remaining.get(**head) == Some(&at_head)
+        });
+        // Lists that disagree on the order are taken in turn.
+        let Some(next) = ready.or(heads.first()).copied() else {
+            break;
+        };
+
+        remaining.remove(next);
+        merged.push(next.clone());
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::mint::Mint;
+    use crate::domain::program::Program;
+
+    fn transfer(
+        signature: &str,
+        source: Account,
+        destination: Account,
+        amount: u64,
+    ) -> Transaction {
+        Transaction::new(
+            signature.to_string(),
+            source,
+            destination,
+            Program::new("program", 9),
+            Mint::new("mint"),
+            Amount::new(amount, 6),
+        )
+    }
+
+    #[test]
+    fn test_add_transaction_tracks_direction_and_balance() {
+        let mut history = AccountHistory::new("wallet");
+
+        let mut wallet = Account::new("wallet", 1, Amount::new(100, 6));
+        wallet.update_post_balance(Amount::new(70, 6));
+        let other = Account::new("other", 2, Amount::new(0, 6));
+
+        history.add_transaction(1, None, transfer("a", wallet.clone(), other.clone(), 20));
+        history.add_transaction(1, None, transfer("a", wallet.clone(), other.clone(), 10));
+        history.add_transaction(1, None, transfer("a", other.clone(), other.clone(), 10));
+
+        let mut wallet = Account::new("wallet", 1, Amount::new(70, 6));
+        wallet.update_post_balance(Amount::new(75, 6));
+        history.add_transaction(2, Some(1_700_000_000), transfer("b", other, wallet, 5));
+
+        assert_eq!(history.entries.len(), 3);
+        assert_eq!(history.entries[0].direction, Direction::Outgoing);
+        assert_eq!(history.entries[0].balance, Amount::new(80, 6));
+        assert_eq!(history.entries[1].balance, Amount::new(70, 6));
+        assert_eq!(history.entries[2].direction, Direction::Incoming);
+        assert_eq!(history.entries[2].balance, Amount::new(75, 6));
+    }
+
+    #[test]
+    fn test_merge_signatures() {
+        let list = |signatures: &[&str]| -> Vec<String> {
+            signatures
+                .iter()
+                .map(|signature| signature.to_string())
+                .collect()
+        };
+
+        // "b" touched both addresses, so "c" is newer than "a".
+        let owner = list(&["b", "a"]);
+        let token_account = list(&["d", "c", "b"]);
+        assert_eq!(
+            merge_signatures(&[owner, token_account]),
+            ["d", "c", "b", "a"]
+        );
+
+        assert_eq!(
+            merge_signatures(&[list(&["a", "b"]), list(&["b", "a"])]),
+            ["a", "b"]
+        );
+        assert!(merge_signatures(&[]).is_empty());
+    }
+}
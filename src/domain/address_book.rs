@@ -0,0 +1,228 @@
+use crate::domain::mint::shorten;
+use crate::domain::program::{
+    ASSOCIATED_TOKEN_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressKind {
+    Program,
+    Exchange,
+    Wallet,
+}
+
+const BUILTIN_LABELS: [(&str, &str, AddressKind); 21] = [
+    (SYSTEM_PROGRAM_ID, "System Program", AddressKind::Program),
+    (TOKEN_PROGRAM_ID, "Token Program", AddressKind::Program),
+    (
+        TOKEN_2022_PROGRAM_ID,
+        "Token-2022 Program",
+        AddressKind::Program,
+    ),
+    (
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        "Associated Token Program",
+        AddressKind::Program,
+    ),
+    (
+        "ComputeBudget111111111111111111111111111111",
+        "Compute Budget Program",
+        AddressKind::Program,
+    ),
+    (
+        "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4",
+        "Jupiter Aggregator v6",
+        AddressKind::Program,
+    ),
+    (
+        "JUP4Fb2cqiRUcaTHdrPC8h2gNsA2ETXiPDD33WcGuJB",
+        "Jupiter Aggregator v4",
+        AddressKind::Program,
+    ),
+    (
+        "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8",
+        "Raydium AMM v4",
+        AddressKind::Program,
+    ),
+    (
+        "5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1",
+        "Raydium AMM Authority",
+        AddressKind::Program,
+    ),
+    (
+        "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK",
+        "Raydium CLMM",
+        AddressKind::Program,
+    ),
+    (
+        "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C",
+        "Raydium CPMM",
+        AddressKind::Program,
+    ),
+    (
+        "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc",
+        "Orca Whirlpool",
+        AddressKind::Program,
+    ),
+    (
+        "9W959DqEETiGZocYWCQPaJ6sBmUzgfxXfqGeTEdp3aQP",
+        "Orca Token Swap v2",
+        AddressKind::Program,
+    ),
+    (
+        "5tzFkiKscXHK5ZXCGbXZxdw7gTjjD1mBwuoFbhUvuAi9",
+        "Binance Hot Wallet",
+        AddressKind::Exchange,
+    ),
+    (
+        "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+        "Binance Hot Wallet 2",
+        AddressKind::Exchange,
+    ),
+    (
+        "2ojv9BAiHUrvsm9gxDe7fJSzbNZSJcxZvf8dqmWGHG8S",
+        "Binance Hot Wallet 3",
+        AddressKind::Exchange,
+    ),
+    (
+        "H8sMJSCQxfKiFTCfDR3DUMLPwcRbM61LGFJ8N4dK3WjS",
+        "Coinbase Hot Wallet",
+        AddressKind::Exchange,
+    ),
+    (
+        "2AQdpHJ2JpcEgPiATUXjQxA8QmafFegfQwSLWSprPicm",
+        "Coinbase Hot Wallet 2",
+        AddressKind::Exchange,
+    ),
+    (
+        "FWznbcNXWQuHTawe9RxvQ2LdCENssh12dsznf4RiouN5",
+        "Kraken Hot Wallet",
+        AddressKind::Exchange,
+    ),
+    (
+        "5VCwKtCXgCJ6kit5FybXjvriW3xELsFDhYrPSqtJNmcD",
+        "OKX Hot Wallet",
+        AddressKind::Exchange,
+    ),
+    (
+        "AC5RDfQFmDS1deWZos921JfqscXdByf8BKHs5ACWjtW2",
+        "Bybit Hot Wallet",
+        AddressKind::Exchange,
+    ),
+];
+
+#[derive(Debug, Clone)]
+struct Entry {
+    label: String,
+    kind: AddressKind,
+}
+
+// Human readable names for addresses. Unknown addresses fall back to their
+// shortened form.
+#[derive(Debug, Clone, Default)]
+pub struct AddressBook {
+    entries: HashMap<String, Entry>,
+}
+
+impl AddressBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Well-known programs and exchange hot wallets.
+    pub fn builtin() -> Self {
+        let mut address_book = Self::new();
+
+        for (address, label, kind) in BUILTIN_LABELS {
+            address_book.insert(address, label, kind);
+        }
+
+        address_book
+    }
+
+    pub fn insert<U: ToString, V: ToString>(&mut self, address: U, label: V, kind: AddressKind) {
+        self.entries.insert(
+            address.to_string(),
+            Entry {
+                label: label.to_string(),
+                kind,
+            },
+        );
+    }
+
+    pub fn get(&self, address: &str) -> Option<&str> {
+        self.entries.get(address).map(|entry| entry.label.as_str())
+    }
+
+    pub fn kind(&self, address: &str) -> Option<AddressKind> {
+        self.entries.get(address).map(|entry| entry.kind)
+    }
+
+    pub fn label(&self, address: &str) -> String {
+        match self.get(address) {
+            Some(label) => label.to_string(),
+            None => shorten(address),
+        }
+    }
+
+    // Programs and exchanges, where funds stop being attributable to a wallet.
+    pub fn terminals(&self) -> impl Iterator<Item = &str> {
+        self.entries
+            .iter()
+            .filter(|(_, entry)| entry.kind != AddressKind::Wallet)
+            .map(|(address, _)| address.as_str())
+    }
+
+    // Known labels of the given addresses, for outputs that keep full addresses.
+    pub fn labels<'a, I: IntoIterator<Item = &'a str>>(
+        &self,
+        addresses: I,
+    ) -> BTreeMap<String, String> {
+        addresses
+            .into_iter()
+            .filter_map(|address| {
+                self.get(address)
+                    .map(|label| (address.to_string(), label.to_string()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label() {
+        let mut address_book = AddressBook::builtin();
+        address_book.insert(
+            "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+            "Exchange",
+            AddressKind::Exchange,
+        );
+
+        assert_eq!(
+            address_book.label("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM"),
+            "Exchange"
+        );
+        assert_eq!(
+            address_book.label("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU"),
+            "7xKX…gAsU"
+        );
+        assert_eq!(address_book.label(TOKEN_PROGRAM_ID), "Token Program");
+        assert!(address_book
+            .terminals()
+            .any(|address| address == SYSTEM_PROGRAM_ID));
+        assert_eq!(
+            address_book
+                .labels([
+                    SYSTEM_PROGRAM_ID,
+                    "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU"
+                ])
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,362 @@
+use crate::domain::address_book::AddressBook;
+use crate::domain::block::Block;
+use crate::domain::filter::TransferFilter;
+use crate::domain::transaction::Transaction;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+const RATE_PERIOD: Duration = Duration::from_secs(60);
+
+// At least `count` matching transfers from the same source within `within`.
+#[derive(Debug, Clone, Copy)]
+pub struct Burst {
+    pub count: usize,
+    pub within: Duration,
+}
+
+// Fires for every transfer passing the filter, or with a burst, once the
+// same source sent enough of them in a short time.
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    pub name: String,
+    pub filter: TransferFilter,
+    pub burst: Option<Burst>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Alert {
+    pub rule: String,
+    pub slot: u64,
+    pub message: String,
+    pub transaction: Transaction,
+    // Alerts dropped by the rate limit since the previous one went out.
+    pub suppressed: usize,
+}
+
+// Turns blocks into alerts. The same alert (rule and transfer, or rule and
+// source for bursts) fires at most once per `dedup` period, and at most
+// `max_per_minute` alerts go out per minute, the rest are counted. Bursts are
+// timed by the block time, in seconds, so blocks caught up on after a
+// reconnect do not bunch up into a burst.
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    dedup: Duration,
+    max_per_minute: Option<usize>,
+    address_book: AddressBook,
+    fired: HashMap<String, Instant>,
+    bursts: HashMap<(usize, String), VecDeque<i64>>,
+    latest_time: Option<i64>,
+    sent: VecDeque<Instant>,
+    suppressed: usize,
+}
+
+impl AlertEngine {
+    pub fn new(
+        rules: Vec<AlertRule>,
+        dedup: Duration,
+        max_per_minute: Option<usize>,
+        address_book: AddressBook,
+    ) -> Self {
+        Self {
+            rules,
+            dedup,
+            max_per_minute,
+            address_book,
+            fired: HashMap::new(),
+            bursts: HashMap::new(),
+            latest_time: None,
+            sent: VecDeque::new(),
+            suppressed: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    // Blocks without a block time are placed at the time they were received,
+    // in seconds since the epoch.
+    pub fn evaluate(&mut self, block: &Block, now: Instant, received_at: i64) -> Vec<Alert> {
+        let dedup = self.dedup;
+        self.fired
+            .retain(|_, fired_at| now.duration_since(*fired_at) < dedup);
+
+        let time = block.block_time.unwrap_or(received_at);
+        let latest = self.latest_time.map_or(time, |latest| latest.max(time));
+        self.latest_time = Some(latest);
+        let rules = &self.rules;
+        self.bursts.retain(|(index, _), times| {
+            if let Some(burst) = rules[*index].burst {
+                expire(times, latest, burst.within);
+            }
+            !times.is_empty()
+        });
+
+        let mut alerts = Vec::new();
+        for transaction in &block.transactions {
+            for index in 0..self.rules.len() {
+                if let Some((key, message)) = self.check(index, transaction, time) {
+                    if self.fired.contains_key(&key) {
+                        continue;
+                    }
+                    self.fired.insert(key, now);
+
+                    if let Some(suppressed) = self.admit(now) {
+                        alerts.push(Alert {
+                            rule: self.rules[index].name.clone(),
+                            slot: block.slot,
+                            message,
+                            transaction: transaction.clone(),
+                            suppressed,
+                        });
+                    }
+                }
+            }
+        }
+
+        alerts
+    }
+
+    // The dedup key and message when the rule fires for the transfer.
+    fn check(
+        &mut self,
+        index: usize,
+        transaction: &Transaction,
+        time: i64,
+    ) -> Option<(String, String)> {
+        let rule = &self.rules[index];
+        if !rule.filter.matches(transaction) {
+            return None;
+        }
+
+        let source = &transaction.source.address;
+        let Some(burst) = rule.burst else {
+            return Some((
+                format!(
+                    "{}|{}|{}",
+                    rule.name, transaction.signature, transaction.instruction_index
+                ),
+                format!(
+                    "{}: {:?} sent {} {} to {:?} ({})",
+                    rule.name,
+                    self.address_book.label(source),
+                    transaction.amount(),
+                    transaction.token.label(),
+                    self.address_book.label(&transaction.destination.address),
+                    transaction.signature
+                ),
+            ));
+        };
+
+        let times = self.bursts.entry((index, source.clone())).or_default();
+        times.push_back(time);
+        expire(times, time, burst.within);
+        if times.len() < burst.count {
+            return None;
+        }
+
+        Some((
+            format!("{}|{}", rule.name, source),
+            format!(
+                "{}: {:?} made {} transfers within {}s, latest {} {} to {:?} ({})",
+                rule.name,
+                self.address_book.label(source),
+                times.len(),
+                burst.within.as_secs(),
+                transaction.amount(),
+                transaction.token.label(),
+                self.address_book.label(&transaction.destination.address),
+                transaction.signature
+            ),
+        ))
+    }
+
+    // The number of alerts suppressed so far when one may go out now.
+    fn admit(&mut self, now: Instant) -> Option<usize> {
+        while self
+            .sent
+            .front()
+            .is_some_and(|time| now.duration_since(*time) >= RATE_PERIOD)
+        {
+            self.sent.pop_front();
+        }
+
+        if self
+            .max_per_minute
+            .is_some_and(|max| self.sent.len() >= max)
+        {
+            self.suppressed += 1;
+            return None;
+        }
+
+        self.sent.push_back(now);
+        Some(std::mem::take(&mut self.suppressed))
+    }
+}
+
+// Drops the times more than `within` before `latest`.
+fn expire(times: &mut VecDeque<i64>, latest: i64, within: Duration) {
+    let within = i64::try_from(within.as_secs()).unwrap_or(i64::MAX);
+    while times
+        .front()
+        .is_some_and(|time| latest.saturating_sub(*time) > within)
+    {
+        times.pop_front();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::amount::Amount;
+    use crate::domain::mint::Mint;
+    use crate::domain::program::Program;
+
+    fn block(slot: u64, transfers: &[(&str, &str, u64)]) -> Block {
+        let mut block = Block::new(slot, "hash".to_string());
+        for (index, (source, destination, amount)) in transfers.iter().enumerate() {
+            block.add_transaction(Transaction::new(
+                format!("sig-{}-{}", slot, index),
+                Account::new(*source, 1, Amount::zero(6)),
+                Account::new(*destination, 2, Amount::zero(6)),
+                Program::new("program", 3),
+                "USDC".parse::<Mint>().unwrap(),
+                Amount::new(*amount, 6),
+            ));
+        }
+
+        block
+    }
+
+    fn rule(name: &str, min_amount: Option<&str>, burst: Option<Burst>) -> AlertRule {
+        let mut filter = TransferFilter::new();
+        filter.min_amount = min_amount.map(|amount| amount.parse().unwrap());
+
+        AlertRule {
+            name: name.to_string(),
+            filter,
+            burst,
+        }
+    }
+
+    #[test]
+    fn test_threshold_rule_and_dedup() {
+        let now = Instant::now();
+        let mut engine = AlertEngine::new(
+            vec![rule("whale", Some("1000"), None)],
+            Duration::from_secs(60),
+            None,
+            AddressBook::new(),
+        );
+        let large = block(1, &[("alice", "bob", 5_000_000_000), ("alice", "bob", 1)]);
+
+        let alerts = engine.evaluate(&large, now, 0);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rule, "whale");
+        assert_eq!(
+            alerts[0].message,
+            "whale: \"alice\" sent 5000 USDC to \"bob\" (sig-1-0)"
+        );
+
+        // The same block again, e.g. after a resubscription.
+        assert!(engine.evaluate(&large, now, 0).is_empty());
+        assert_eq!(
+            engine
+                .evaluate(&large, now + Duration::from_secs(61), 0)
+                .len(),
+            1
+        );
+
+        // Two large transfers of one transaction each alert.
+        let mut split = Block::new(2, "hash".to_string());
+        for (index, destination) in ["bob", "carol"].iter().enumerate() {
+            let mut transfer = Transaction::new(
+                "sig-2".to_string(),
+                Account::new("alice", 1, Amount::zero(6)),
+                Account::new(*destination, 2, Amount::zero(6)),
+                Program::new("program", 3),
+                "USDC".parse::<Mint>().unwrap(),
+                Amount::new(2_000_000_000, 6),
+            );
+            transfer.update_instruction_index(index);
+            split.add_transaction(transfer);
+        }
+        assert_eq!(engine.evaluate(&split, now, 0).len(), 2);
+    }
+
+    #[test]
+    fn test_burst_rule() {
+        let now = Instant::now();
+        let burst = Burst {
+            count: 3,
+            within: Duration::from_secs(10),
+        };
+        let mut engine = AlertEngine::new(
+            vec![rule("burst", None, Some(burst))],
+            Duration::from_secs(300),
+            None,
+            AddressBook::new(),
+        );
+        let timed = |slot: u64, transfers: &[(&str, &str, u64)], block_time: i64| {
+            let mut block = block(slot, transfers);
+            block.update_block_time(Some(block_time));
+            block
+        };
+
+        assert!(engine
+            .evaluate(
+                &timed(1, &[("alice", "bob", 1), ("carol", "bob", 1)], 1_000),
+                now,
+                0
+            )
+            .is_empty());
+        assert!(engine
+            .evaluate(&timed(2, &[("alice", "bob", 1)], 1_020), now, 0)
+            .is_empty());
+        // Carol's window has passed.
+        assert_eq!(engine.bursts.len(), 1);
+
+        // Caught up on at once, but produced 25 seconds apart.
+        assert!(engine
+            .evaluate(&timed(3, &[("alice", "dave", 1)], 1_045), now, 0)
+            .is_empty());
+        assert!(engine
+            .evaluate(&timed(4, &[("alice", "dave", 1)], 1_070), now, 0)
+            .is_empty());
+
+        let alerts = engine.evaluate(
+            &timed(5, &[("alice", "dave", 1), ("alice", "erin", 1)], 1_075),
+            now,
+            0,
+        );
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].message.contains("made 3 transfers within 10s"));
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let now = Instant::now();
+        let mut engine = AlertEngine::new(
+            vec![rule("all", None, None)],
+            Duration::from_secs(300),
+            Some(2),
+            AddressBook::new(),
+        );
+
+        let alerts = engine.evaluate(
+            &block(
+                1,
+                &[("a", "b", 1), ("c", "d", 1), ("e", "f", 1), ("g", "h", 1)],
+            ),
+            now,
+            0,
+        );
+        assert_eq!(alerts.len(), 2);
+
+        let alerts = engine.evaluate(&block(2, &[("a", "b", 1)]), now + RATE_PERIOD, 0);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].suppressed, 2);
+    }
+}
@@ -0,0 +1,181 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+
+// A token amount in base units together with the mint decimals, so that
+// balances and differences stay exact instead of going through `f64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Amount {
+    pub raw: u64,
+    pub decimals: u8,
+}
+
+impl Amount {
+    pub fn new(raw: u64, decimals: u8) -> Self {
+        Self { raw, decimals }
+    }
+
+    pub fn zero(decimals: u8) -> Self {
+        Self { raw: 0, decimals }
+    }
+
+    pub fn parse(raw: &str, decimals: u8) -> Result<Self, String> {
+        raw.parse::<u64>()
+            .map(|raw| Self { raw, decimals })
+            .map_err(|e| format!("invalid raw amount {:?}: {}", raw, e))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.raw == 0
+    }
+
+    pub fn checked_add(&self, other: Amount) -> Option<Amount> {
+        if self.decimals != other.decimals {
+            return None;
+        }
+
+        self.raw
+            .checked_add(other.raw)
+            .map(|raw| Amount::new(raw, self.decimals))
+    }
+
+    pub fn checked_sub(&self, other: Amount) -> Option<Amount> {
+        if self.decimals != other.decimals {
+            return None;
+        }
+
+        self.raw
+            .checked_sub(other.raw)
+            .map(|raw| Amount::new(raw, self.decimals))
+    }
+
+    pub fn saturating_sub(&self, other: Amount) -> Amount {
+        self.checked_sub(other)
+            .unwrap_or_else(|| Amount::zero(self.decimals))
+    }
+
+    // Lossy, only meant for ranking and scaling amounts, never for balances.
+    pub fn to_f64(&self) -> f64 {
+        self.raw as f64 / 10f64.powi(self.decimals as i32)
+    }
+}
+
+impl PartialOrd for Amount {
+    // Amounts of different precision are not comparable.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.decimals == other.decimals {
+            Some(self.raw.cmp(&other.raw))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_ui(f, self.raw.to_string(), self.decimals)
+    }
+}
+
+fn write_ui(f: &mut fmt::Formatter<'_>, digits: String, decimals: u8) -> fmt::Result {
+    let decimals = decimals as usize;
+
+    if decimals == 0 {
+        return write!(f, "{}", digits);
+    }
+
+    let digits = format!("{:0>width$}", digits, width = decimals + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        write!(f, "{}", integer)
+    } else {
+        write!(f, "{}.{}", integer, fraction)
+    }
+}
+
+// A sum of amounts of one mint. Each amount fits in a u64, their sum may not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Volume {
+    pub raw: u128,
+    pub decimals: u8,
+}
+
+impl Volume {
+    pub fn new(raw: u128, decimals: u8) -> Self {
+        Self { raw, decimals }
+    }
+
+    pub fn add(&mut self, amount: Amount) {
+        self.raw += amount.raw as u128;
+    }
+}
+
+impl fmt::Display for Volume {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_ui(f, self.raw.to_string(), self.decimals)
+    }
+}
+
+// Raw amounts are serialized as strings, since JSON consumers often parse
+// numbers as doubles and would lose precision above 2^53.
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Amount", 3)?;
+        state.serialize_field("raw", &self.raw.to_string())?;
+        state.serialize_field("decimals", &self.decimals)?;
+        state.serialize_field("ui", &self.to_string())?;
+        state.end()
+    }
+}
+
+impl Serialize for Volume {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Volume", 3)?;
+        state.serialize_field("raw", &self.raw.to_string())?;
+        state.serialize_field("decimals", &self.decimals)?;
+        state.serialize_field("ui", &self.to_string())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amount_display() {
+        assert_eq!(Amount::new(1_500_000, 6).to_string(), "1.5");
+        assert_eq!(Amount::new(42, 6).to_string(), "0.000042");
+        assert_eq!(Amount::new(7_000_000, 6).to_string(), "7");
+        assert_eq!(Amount::new(123, 0).to_string(), "123");
+        assert_eq!(
+            Amount::new(u64::MAX, 9).to_string(),
+            "18446744073.709551615"
+        );
+    }
+
+    #[test]
+    fn test_amount_arithmetic() {
+        let balance = Amount::parse("18446744073709551615", 6).unwrap();
+        let spent = Amount::new(1, 6);
+
+        assert_eq!(
+            balance.checked_sub(spent),
+            Some(Amount::new(u64::MAX - 1, 6))
+        );
+        assert_eq!(balance.checked_add(spent), None);
+        assert_eq!(spent.saturating_sub(balance), Amount::zero(6));
+        assert_eq!(spent.checked_add(Amount::new(1, 9)), None);
+        assert!(spent < balance);
+        assert!(Amount::parse("1.5", 6).is_err());
+        assert_eq!(Amount::new(1_500_000, 6).to_f64(), 1.5);
+
+        let mut volume = Volume::new(0, 9);
+        volume.add(Amount::new(u64::MAX, 9));
+        volume.add(Amount::new(u64::MAX, 9));
+        assert_eq!(volume.to_string(), "36893488147.41910323");
+    }
+}
@@ -1,10 +1,18 @@
+use crate::domain::commitment::Commitment;
+use crate::domain::event::Event;
 use crate::domain::transaction::Transaction;
+use serde::Serialize;
+use std::collections::HashSet;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Block {
     pub slot: u64,
     pub blockhash: String,
+    pub block_time: Option<i64>,
+    // Below finalized, a finality update follows once the block settles.
+    pub commitment: Commitment,
     pub transactions: Vec<Transaction>,
+    pub events: Vec<Event>,
 }
 
 impl Block {
@@ -12,22 +20,73 @@ impl Block {
         Self {
             slot,
             blockhash,
+            block_time: None,
+            commitment: Commitment::Finalized,
             transactions: Vec::new(),
+            events: Vec::new(),
         }
     }
 
+    pub fn update_block_time(&mut self, block_time: Option<i64>) {
+        self.block_time = block_time;
+    }
+
+    pub fn update_commitment(&mut self, commitment: Commitment) {
+        self.commitment = commitment;
+    }
+
     pub fn add_transaction(&mut self, transaction: Transaction) {
-        if transaction.amount() > 0.0 {
+        if !transaction.amount().is_zero() {
             self.transactions.push(transaction);
         }
     }
+
+    pub fn add_event(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    // Keeps the transfers matching the predicate, and the events of the
+    // transactions that still have one.
+    pub fn retain_transactions<F: FnMut(&Transaction) -> bool>(&mut self, predicate: F) {
+        self.transactions.retain(predicate);
+
+        let signatures: HashSet<&str> = self
+            .transactions
+            .iter()
+            .map(|transaction| transaction.signature.as_str())
+            .collect();
+        self.events
+            .retain(|event| signatures.contains(event.signature.as_str()));
+    }
+
+    // Every address referenced by the transfers and events of the block.
+    pub fn addresses(&self) -> Vec<&str> {
+        let mut addresses: Vec<&str> = Vec::new();
+
+        for transaction in &self.transactions {
+            addresses.push(&transaction.source.address);
+            addresses.push(&transaction.destination.address);
+            addresses.push(&transaction.program.address);
+        }
+        for event in &self.events {
+            addresses.push(&event.program.address);
+            addresses.extend(event.kind.accounts());
+        }
+
+        addresses.sort_unstable();
+        addresses.dedup();
+
+        addresses
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::domain::account::Account;
-    use crate::domain::program::Program;
+    use crate::domain::amount::Amount;
+    use crate::domain::mint::Mint;
+    use crate::domain::program::{Program, ProgramKind};
 
     #[test]
     fn test_block_add_transaction() {
@@ -35,23 +94,31 @@ mod tests {
         let source = Account {
             address: "source".to_string(),
             index: 0,
-            pre_balance: 100.0,
-            post_balance: 50.0,
+            pre_balance: Amount::new(100_000_000, 6),
+            post_balance: Amount::new(50_000_000, 6),
         };
         let destination = Account {
             address: "destination".to_string(),
             index: 1,
-            pre_balance: 100.0,
-            post_balance: 150.0,
+            pre_balance: Amount::new(100_000_000, 6),
+            post_balance: Amount::new(150_000_000, 6),
         };
         let program = Program {
             address: "program".to_string(),
             index: 2,
+            kind: ProgramKind::Other,
         };
 
-        let token = "USDC".to_string();
-        let transaction =
-            Transaction::new("signature".to_string(), source, destination, program, token);
+        let token = Mint::new("mint");
+        let amount = Amount::new(50_000_000, 6);
+        let transaction = Transaction::new(
+            "signature".to_string(),
+            source,
+            destination,
+            program,
+            token,
+            amount,
+        );
 
         block.add_transaction(transaction.clone());
         assert_eq!(block.transactions.len(), 1);
@@ -62,23 +129,31 @@ mod tests {
         let source = Account {
             address: "source".to_string(),
             index: 0,
-            pre_balance: 100.0,
-            post_balance: 100.0,
+            pre_balance: Amount::new(100_000_000, 6),
+            post_balance: Amount::new(100_000_000, 6),
         };
         let destination = Account {
             address: "destination".to_string(),
             index: 1,
-            pre_balance: 100.0,
-            post_balance: 100.0,
+            pre_balance: Amount::new(100_000_000, 6),
+            post_balance: Amount::new(100_000_000, 6),
         };
         let program = Program {
             address: "program".to_string(),
             index: 2,
+            kind: ProgramKind::Other,
         };
 
-        let token = "USDC".to_string();
-        let transaction =
-            Transaction::new("signature".to_string(), source, destination, program, token);
+        let token = Mint::new("mint");
+        let amount = Amount::zero(6);
+        let transaction = Transaction::new(
+            "signature".to_string(),
+            source,
+            destination,
+            program,
+            token,
+            amount,
+        );
 
         block.add_transaction(transaction.clone());
         assert_eq!(block.transactions.len(), 2);
@@ -90,23 +165,31 @@ mod tests {
         let source = Account {
             address: "source".to_string(),
             index: 0,
-            pre_balance: 100.0,
-            post_balance: 100.0,
+            pre_balance: Amount::new(100_000_000, 6),
+            post_balance: Amount::new(100_000_000, 6),
         };
         let destination = Account {
             address: "destination".to_string(),
             index: 1,
-            pre_balance: 100.0,
-            post_balance: 100.0,
+            pre_balance: Amount::new(100_000_000, 6),
+            post_balance: Amount::new(100_000_000, 6),
         };
         let program = Program {
             address: "program".to_string(),
             index: 2,
+            kind: ProgramKind::Other,
         };
 
-        let token = "USDC".to_string();
-        let transaction =
-            Transaction::new("signature".to_string(), source, destination, program, token);
+        let token = Mint::new("mint");
+        let amount = Amount::zero(6);
+        let transaction = Transaction::new(
+            "signature".to_string(),
+            source,
+            destination,
+            program,
+            token,
+            amount,
+        );
 
         block.add_transaction(transaction.clone());
         assert_eq!(block.transactions.len(), 0);
@@ -0,0 +1,159 @@
+use crate::domain::block::Block;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+// How settled a block must be before it is output. Below finalized, a block
+// may still be dropped when the cluster settles on another fork.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Commitment {
+    Processed,
+    Confirmed,
+    #[default]
+    Finalized,
+}
+
+impl FromStr for Commitment {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "processed" => Ok(Commitment::Processed),
+            "confirmed" => Ok(Commitment::Confirmed),
+            "finalized" => Ok(Commitment::Finalized),
+            _ => Err(format!(
+                "Unknown commitment {}, expected processed, confirmed or finalized",
+                value
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Commitment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Commitment::Processed => write!(f, "processed"),
+            Commitment::Confirmed => write!(f, "confirmed"),
+            Commitment::Finalized => write!(f, "finalized"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Finality {
+    Finalized,
+    // The block was not finalized: its slot was skipped on the finalized
+    // fork, or another block was finalized in it.
+    Dropped,
+}
+
+// What became of a block output before it was finalized.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FinalityUpdate {
+    pub slot: u64,
+    pub blockhash: String,
+    pub finality: Finality,
+}
+
+// Blocks output below finalized, waiting to learn whether they made it.
+#[derive(Default)]
+pub struct FinalityTracker {
+    provisional: BTreeMap<u64, String>,
+}
+
+impl FinalityTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn provisional(&mut self, block: &Block) {
+        self.provisional.insert(block.slot, block.blockhash.clone());
+    }
+
+    // The lowest slot still waiting, where a finality check starts.
+    pub fn first_slot(&self) -> Option<u64> {
+        self.provisional.keys().next().copied()
+    }
+
+    // The slots waiting up to the finalized one, those settled next.
+    pub fn slots_through(&self, finalized_slot: u64) -> Vec<u64> {
+        self.provisional
+            .range(..=finalized_slot)
+            .map(|(slot, _)| *slot)
+            .collect()
+    }
+
+    // Settles the slots up to the finalized one, given the blockhashes of the
+    // finalized blocks among them: a block is finalized when the same block
+    // was, and dropped otherwise.
+    pub fn settle(
+        &mut self,
+        finalized_slot: u64,
+        finalized_blockhashes: &HashMap<u64, String>,
+    ) -> Vec<FinalityUpdate> {
+        let waiting = self.provisional.split_off(&(finalized_slot + 1));
+        let settled = std::mem::replace(&mut self.provisional, waiting);
+
+        settled
+            .into_iter()
+            .map(|(slot, blockhash)| FinalityUpdate {
+                finality: if finalized_blockhashes.get(&slot) == Some(&blockhash) {
+                    Finality::Finalized
+                } else {
+                    Finality::Dropped
+                },
+                slot,
+                blockhash,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commitment() {
+        assert_eq!("confirmed".parse(), Ok(Commitment::Confirmed));
+        assert_eq!(Commitment::Processed.to_string(), "processed");
+        assert!("rooted".parse::<Commitment>().is_err());
+        assert!(Commitment::Confirmed < Commitment::Finalized);
+    }
+
+    #[test]
+    fn test_settle() {
+        let mut tracker = FinalityTracker::new();
+        for slot in [10, 11, 12, 13, 15] {
+            tracker.provisional(&Block::new(slot, format!("hash-{}", slot)));
+        }
+        assert_eq!(tracker.slots_through(13), [10, 11, 12, 13]);
+
+        // Slot 11 was skipped on the finalized fork, another block was
+        // finalized in slot 13.
+        let finalized_blockhashes = HashMap::from([
+            (10, "hash-10".to_string()),
+            (12, "hash-12".to_string()),
+            (13, "other".to_string()),
+        ]);
+        let updates = tracker.settle(13, &finalized_blockhashes);
+        assert_eq!(
+            updates
+                .iter()
+                .map(|update| (update.slot, update.finality))
+                .collect::<Vec<_>>(),
+            [
+                (10, Finality::Finalized),
+                (11, Finality::Dropped),
+                (12, Finality::Finalized),
+                (13, Finality::Dropped)
+            ]
+        );
+        assert_eq!(updates[1].blockhash, "hash-11");
+        assert_eq!(updates[3].blockhash, "hash-13");
+        assert_eq!(tracker.first_slot(), Some(15));
+    }
+}
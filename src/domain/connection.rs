@@ -0,0 +1,47 @@
+use serde::Serialize;
+use std::fmt;
+
+// State of the connection to the cluster's slot updates.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ConnectionState {
+    Connected,
+    Disconnected { reason: String },
+    Reconnecting { attempt: u32, delay_seconds: u64 },
+}
+
+impl fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConnectionState::Connected => write!(f, "connected"),
+            ConnectionState::Disconnected { reason } => write!(f, "disconnected: {}", reason),
+            ConnectionState::Reconnecting {
+                attempt,
+                delay_seconds,
+            } => write!(
+                f,
+                "reconnecting in {}s (attempt {})",
+                delay_seconds, attempt
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_state() {
+        let state = ConnectionState::Reconnecting {
+            attempt: 3,
+            delay_seconds: 4,
+        };
+
+        assert_eq!(state.to_string(), "reconnecting in 4s (attempt 3)");
+        assert_eq!(
+            serde_json::to_value(&state).unwrap(),
+            serde_json::json!({ "state": "reconnecting", "attempt": 3, "delay_seconds": 4 })
+        );
+    }
+}
@@ -0,0 +1,400 @@
+use crate::domain::program::Program;
+use serde::{Serialize, Serializer};
+
+// Token account addresses and raw amounts as they appear in the instruction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    Transfer {
+        source: String,
+        destination: String,
+        authority: String,
+        #[serde(serialize_with = "as_string")]
+        amount: u64,
+    },
+    TransferChecked {
+        source: String,
+        mint: String,
+        destination: String,
+        authority: String,
+        #[serde(serialize_with = "as_string")]
+        amount: u64,
+        decimals: u8,
+    },
+    MintTo {
+        mint: String,
+        account: String,
+        authority: String,
+        #[serde(serialize_with = "as_string")]
+        amount: u64,
+    },
+    MintToChecked {
+        mint: String,
+        account: String,
+        authority: String,
+        #[serde(serialize_with = "as_string")]
+        amount: u64,
+        decimals: u8,
+    },
+    Burn {
+        account: String,
+        mint: String,
+        authority: String,
+        #[serde(serialize_with = "as_string")]
+        amount: u64,
+    },
+    BurnChecked {
+        account: String,
+        mint: String,
+        authority: String,
+        #[serde(serialize_with = "as_string")]
+        amount: u64,
+        decimals: u8,
+    },
+    Approve {
+        source: String,
+        delegate: String,
+        owner: String,
+        #[serde(serialize_with = "as_string")]
+        amount: u64,
+    },
+    ApproveChecked {
+        source: String,
+        mint: String,
+        delegate: String,
+        owner: String,
+        #[serde(serialize_with = "as_string")]
+        amount: u64,
+        decimals: u8,
+    },
+    Revoke {
+        source: String,
+        owner: String,
+    },
+    CloseAccount {
+        account: String,
+        destination: String,
+        owner: String,
+    },
+    FreezeAccount {
+        account: String,
+        mint: String,
+        authority: String,
+    },
+    ThawAccount {
+        account: String,
+        mint: String,
+        authority: String,
+    },
+    TransferCheckedWithFee {
+        source: String,
+        mint: String,
+        destination: String,
+        authority: String,
+        #[serde(serialize_with = "as_string")]
+        amount: u64,
+        decimals: u8,
+        #[serde(serialize_with = "as_string")]
+        fee: u64,
+    },
+    WithdrawWithheldTokensFromMint {
+        mint: String,
+        destination: String,
+        authority: String,
+    },
+    WithdrawWithheldTokensFromAccounts {
+        mint: String,
+        destination: String,
+        authority: String,
+        sources: Vec<String>,
+    },
+    HarvestWithheldTokensToMint {
+        mint: String,
+        sources: Vec<String>,
+    },
+    // Confidential amounts are encrypted, only deposits and withdrawals are in the clear.
+    ConfidentialDeposit {
+        account: String,
+        mint: String,
+        #[serde(serialize_with = "as_string")]
+        amount: u64,
+        decimals: u8,
+    },
+    ConfidentialWithdraw {
+        account: String,
+        mint: String,
+        #[serde(serialize_with = "as_string")]
+        amount: u64,
+        decimals: u8,
+    },
+    ConfidentialTransfer {
+        source: String,
+        mint: String,
+        destination: String,
+    },
+    SyncNative {
+        account: String,
+    },
+    // System program lamport movements, addresses are wallets rather than token accounts.
+    SystemTransfer {
+        source: String,
+        destination: String,
+        #[serde(serialize_with = "as_string")]
+        lamports: u64,
+    },
+    SystemTransferWithSeed {
+        source: String,
+        base: String,
+        destination: String,
+        #[serde(serialize_with = "as_string")]
+        lamports: u64,
+    },
+    CreateAccount {
+        source: String,
+        account: String,
+        #[serde(serialize_with = "as_string")]
+        lamports: u64,
+    },
+}
+
+impl EventKind {
+    pub fn amount(&self) -> Option<u64> {
+        match self {
+            EventKind::Transfer { amount, .. }
+            | EventKind::TransferChecked { amount, .. }
+            | EventKind::MintTo { amount, .. }
+            | EventKind::MintToChecked { amount, .. }
+            | EventKind::Burn { amount, .. }
+            | EventKind::BurnChecked { amount, .. }
+            | EventKind::Approve { amount, .. }
+            | EventKind::ApproveChecked { amount, .. }
+            | EventKind::TransferCheckedWithFee { amount, .. }
+            | EventKind::ConfidentialDeposit { amount, .. }
+            | EventKind::ConfidentialWithdraw { amount, .. } => Some(*amount),
+            EventKind::SystemTransfer { lamports, .. }
+            | EventKind::SystemTransferWithSeed { lamports, .. }
+            | EventKind::CreateAccount { lamports, .. } => Some(*lamports),
+            _ => None,
+        }
+    }
+
+    // Fee withheld by the Token-2022 transfer fee extension, when the instruction states it.
+    pub fn fee(&self) -> Option<u64> {
+        match self {
+            EventKind::TransferCheckedWithFee { fee, .. } => Some(*fee),
+            _ => None,
+        }
+    }
+
+    // Source and destination token accounts when the event moves tokens between them.
+    pub fn transfer(&self) -> Option<(&str, &str)> {
+        match self {
+            EventKind::Transfer {
+                source,
+                destination,
+                ..
+            }
+            | EventKind::TransferChecked {
+                source,
+                destination,
+                ..
+            }
+            | EventKind::TransferCheckedWithFee {
+                source,
+                destination,
+                ..
+            }
+            | EventKind::ConfidentialTransfer {
+                source,
+                destination,
+                ..
+            }
+            | EventKind::SystemTransfer {
+                source,
+                destination,
+                ..
+            }
+            | EventKind::SystemTransferWithSeed {
+                source,
+                destination,
+                ..
+            }
+            | EventKind::CreateAccount {
+                source,
+                account: destination,
+                ..
+            } => Some((source, destination)),
+            _ => None,
+        }
+    }
+
+    pub fn accounts(&self) -> Vec<&str> {
+        match self {
+            EventKind::Transfer {
+                source,
+                destination,
+                authority,
+                ..
+            } => vec![source, destination, authority],
+            EventKind::TransferChecked {
+                source,
+                mint,
+                destination,
+                authority,
+                ..
+            } => vec![source, mint, destination, authority],
+            EventKind::MintTo {
+                mint,
+                account,
+                authority,
+                ..
+            }
+            | EventKind::MintToChecked {
+                mint,
+                account,
+                authority,
+                ..
+            }
+            | EventKind::Burn {
+                account,
+                mint,
+                authority,
+                ..
+            }
+            | EventKind::BurnChecked {
+                account,
+                mint,
+                authority,
+                ..
+            }
+            | EventKind::FreezeAccount {
+                account,
+                mint,
+                authority,
+            }
+            | EventKind::ThawAccount {
+                account,
+                mint,
+                authority,
+            } => vec![mint, account, authority],
+            EventKind::Approve {
+                source,
+                delegate,
+                owner,
+                ..
+            } => vec![source, delegate, owner],
+            EventKind::ApproveChecked {
+                source,
+                mint,
+                delegate,
+                owner,
+                ..
+            } => vec![source, mint, delegate, owner],
+            EventKind::Revoke { source, owner } => vec![source, owner],
+            EventKind::CloseAccount {
+                account,
+                destination,
+                owner,
+            } => vec![account, destination, owner],
+            EventKind::TransferCheckedWithFee {
+                source,
+                mint,
+                destination,
+                authority,
+                ..
+            } => vec![source, mint, destination, authority],
+            EventKind::WithdrawWithheldTokensFromMint {
+                mint,
+                destination,
+                authority,
+            } => vec![mint, destination, authority],
+            EventKind::WithdrawWithheldTokensFromAccounts {
+                mint,
+                destination,
+                authority,
+                sources,
+            } => [mint, destination, authority]
+                .into_iter()
+                .chain(sources.iter())
+                .map(String::as_str)
+                .collect(),
+            EventKind::HarvestWithheldTokensToMint { mint, sources } => std::iter::once(mint)
+                .chain(sources.iter())
+                .map(String::as_str)
+                .collect(),
+            EventKind::ConfidentialDeposit { account, mint, .. }
+            | EventKind::ConfidentialWithdraw { account, mint, .. } => vec![account, mint],
+            EventKind::ConfidentialTransfer {
+                source,
+                mint,
+                destination,
+            } => vec![source, mint, destination],
+            EventKind::SyncNative { account } => vec![account],
+            EventKind::SystemTransfer {
+                source,
+                destination,
+                ..
+            } => vec![source, destination],
+            EventKind::SystemTransferWithSeed {
+                source,
+                base,
+                destination,
+                ..
+            } => vec![source, base, destination],
+            EventKind::CreateAccount {
+                source, account, ..
+            } => vec![source, account],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Event {
+    pub signature: String,
+    pub program: Program,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+impl Event {
+    pub fn new(signature: String, program: Program, kind: EventKind) -> Self {
+        Self {
+            signature,
+            program,
+            kind,
+        }
+    }
+}
+
+fn as_string<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_kind_transfer() {
+        let transfer = EventKind::TransferChecked {
+            source: "source".to_string(),
+            mint: "mint".to_string(),
+            destination: "destination".to_string(),
+            authority: "authority".to_string(),
+            amount: 42,
+            decimals: 6,
+        };
+        let burn = EventKind::Burn {
+            account: "source".to_string(),
+            mint: "mint".to_string(),
+            authority: "authority".to_string(),
+            amount: 7,
+        };
+
+        assert_eq!(transfer.transfer(), Some(("source", "destination")));
+        assert_eq!(transfer.amount(), Some(42));
+        assert_eq!(burn.transfer(), None);
+        assert_eq!(burn.amount(), Some(7));
+        assert!(burn.accounts().contains(&"mint"));
+    }
+}
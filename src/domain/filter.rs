@@ -0,0 +1,192 @@
+use crate::domain::amount::Amount;
+use crate::domain::block::Block;
+use crate::domain::transaction::Transaction;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::str::FromStr;
+
+const MAX_THRESHOLD_DECIMALS: usize = 18;
+
+// An amount in token units, e.g. `1000000` or `0.5`, compared exactly against
+// transfer amounts whatever the decimals of their mint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Threshold {
+    raw: u128,
+    decimals: u8,
+}
+
+impl Threshold {
+    // How the threshold compares to the amount.
+    pub fn compare(&self, amount: Amount) -> Ordering {
+        // Scaled to the same precision, an overflow can only mean "larger".
+        let threshold = 10u128
+            .checked_pow(amount.decimals as u32)
+            .and_then(|scale| self.raw.checked_mul(scale));
+        let amount = 10u128
+            .checked_pow(self.decimals as u32)
+            .and_then(|scale| (amount.raw as u128).checked_mul(scale));
+
+        match (threshold, amount) {
+            (Some(threshold), Some(amount)) => threshold.cmp(&amount),
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
+
+impl FromStr for Threshold {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+        let digits = format!("{}{}", integer, fraction);
+
+        if digits.is_empty()
+            || !digits.chars().all(|c| c.is_ascii_digit())
+            || fraction.len() > MAX_THRESHOLD_DECIMALS
+        {
+            return Err(format!("{:?} is not a token amount", value));
+        }
+
+        digits
+            .parse::<u128>()
+            .map(|raw| Self {
+                raw,
+                decimals: fraction.len() as u8,
+            })
+            .map_err(|e| format!("{:?} is not a token amount: {}", value, e))
+    }
+}
+
+// Criteria a transfer has to meet to be kept. Every criterion that is set has
+// to match, addresses match when they are any of the given ones. An empty
+// filter keeps everything.
+#[derive(Debug, Clone, Default)]
+pub struct TransferFilter {
+    pub sources: HashSet<String>,
+    pub destinations: HashSet<String>,
+    pub involves: HashSet<String>,
+    pub programs: HashSet<String>,
+    pub mints: HashSet<String>,
+    pub min_amount: Option<Threshold>,
+    pub max_amount: Option<Threshold>,
+}
+
+impl TransferFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+            && self.destinations.is_empty()
+            && self.involves.is_empty()
+            && self.programs.is_empty()
+            && self.mints.is_empty()
+            && self.min_amount.is_none()
+            && self.max_amount.is_none()
+    }
+
+    pub fn matches(&self, transaction: &Transaction) -> bool {
+        let source = &transaction.source.address;
+        let destination = &transaction.destination.address;
+
+        (self.sources.is_empty() || self.sources.contains(source))
+            && (self.destinations.is_empty() || self.destinations.contains(destination))
+            && (self.involves.is_empty()
+                || self.involves.contains(source)
+                || self.involves.contains(destination))
+            && (self.programs.is_empty() || self.programs.contains(&transaction.program.address))
+            && (self.mints.is_empty() || self.mints.contains(&transaction.token.address))
+            && self
+                .min_amount
+                .is_none_or(|min| min.compare(transaction.amount()) != Ordering::Greater)
+            && self
+                .max_amount
+                .is_none_or(|max| max.compare(transaction.amount()) != Ordering::Less)
+    }
+
+    // Drops the transfers that don't match, together with the events of
+    // transactions left without any transfer.
+    pub fn apply(&self, block: &mut Block) {
+        if !self.is_empty() {
+            block.retain_transactions(|transaction| self.matches(transaction));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::mint::Mint;
+    use crate::domain::program::Program;
+
+    fn transaction(source: &str, destination: &str, amount: Amount) -> Transaction {
+        Transaction::new(
+            format!("{}-{}", source, destination),
+            Account::new(source, 1, Amount::zero(amount.decimals)),
+            Account::new(destination, 2, Amount::zero(amount.decimals)),
+            Program::new("program", 3),
+            "USDC".parse::<Mint>().unwrap(),
+            amount,
+        )
+    }
+
+    #[test]
+    fn test_threshold_compare() {
+        let threshold: Threshold = "1000000".parse().unwrap();
+
+        assert_eq!(
+            threshold.compare(Amount::new(1_000_000_000_000, 6)),
+            Ordering::Equal
+        );
+        assert_eq!(
+            threshold.compare(Amount::new(999_999_999_999, 6)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            "0.5"
+                .parse::<Threshold>()
+                .unwrap()
+                .compare(Amount::new(500_000_001, 9)),
+            Ordering::Less
+        );
+        assert!("1e6".parse::<Threshold>().is_err());
+        assert!(".".parse::<Threshold>().is_err());
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let mut filter = TransferFilter::new();
+        assert!(filter.matches(&transaction("alice", "bob", Amount::new(1, 6))));
+
+        filter.involves.insert("treasury".to_string());
+        filter.min_amount = Some("10".parse().unwrap());
+
+        assert!(filter.matches(&transaction("treasury", "bob", Amount::new(10_000_000, 6))));
+        assert!(filter.matches(&transaction("bob", "treasury", Amount::new(20_000_000, 6))));
+        assert!(!filter.matches(&transaction("treasury", "bob", Amount::new(9_999_999, 6))));
+        assert!(!filter.matches(&transaction("alice", "bob", Amount::new(50_000_000, 6))));
+
+        filter
+            .mints
+            .insert("So11111111111111111111111111111111111111112".to_string());
+        assert!(!filter.matches(&transaction("treasury", "bob", Amount::new(10_000_000, 6))));
+    }
+
+    #[test]
+    fn test_filter_apply() {
+        let mut block = Block::new(1, "hash".to_string());
+        block.add_transaction(transaction("alice", "bob", Amount::new(5_000_000, 6)));
+        block.add_transaction(transaction("carol", "dave", Amount::new(1_000_000, 6)));
+        let mut filter = TransferFilter::new();
+        filter.max_amount = Some("2".parse().unwrap());
+
+        filter.apply(&mut block);
+
+        assert_eq!(block.transactions.len(), 1);
+        assert_eq!(block.transactions[0].source.address, "carol");
+    }
+}
@@ -0,0 +1,107 @@
+use serde::Serialize;
+use std::str::FromStr;
+
+// Native SOL shares its address with the wrapped SOL mint.
+pub const NATIVE_MINT: &str = "So11111111111111111111111111111111111111112";
+pub const NATIVE_DECIMALS: u8 = 9;
+
+const KNOWN_MINTS: [(&str, &str); 5] = [
+    ("SOL", NATIVE_MINT),
+    ("USDC", "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
+    ("USDT", "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB"),
+    ("PYUSD", "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo"),
+    ("JUP", "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Mint {
+    pub address: String,
+    pub symbol: Option<String>,
+}
+
+impl Mint {
+    pub fn new<U: ToString>(address: U) -> Self {
+        let address = address.to_string();
+        let symbol = KNOWN_MINTS
+            .iter()
+            .find(|(_, known_address)| *known_address == address)
+            .map(|(symbol, _)| symbol.to_string());
+
+        Self { address, symbol }
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.address == NATIVE_MINT
+    }
+
+    pub fn label(&self) -> String {
+        match &self.symbol {
+            Some(symbol) => symbol.clone(),
+            None => shorten(&self.address),
+        }
+    }
+}
+
+impl FromStr for Mint {
+    type Err = String;
+
+    // Accepts either a well-known symbol (case insensitive) or a base58 mint address.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Some((_, address)) = KNOWN_MINTS
+            .iter()
+            .find(|(symbol, _)| symbol.eq_ignore_ascii_case(value))
+        {
+            return Ok(Mint::new(address));
+        }
+
+        let is_base58 = value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l'));
+
+        if (32..=44).contains(&value.len()) && is_base58 {
+            Ok(Mint::new(value))
+        } else {
+            Err(format!(
+                "{:?} is neither a known symbol nor a mint address",
+                value
+            ))
+        }
+    }
+}
+
+pub fn shorten(address: &str) -> String {
+    if address.chars().count() <= 8 {
+        return address.to_string();
+    }
+
+    let head: String = address.chars().take(4).collect();
+    let tail: String = address.chars().skip(address.chars().count() - 4).collect();
+
+    format!("{}…{}", head, tail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mint_from_symbol() {
+        let mint: Mint = "usdc".parse().unwrap();
+
+        assert_eq!(mint.address, "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+        assert_eq!(mint.label(), "USDC");
+        assert!(!mint.is_native());
+        assert!("SOL".parse::<Mint>().unwrap().is_native());
+    }
+
+    #[test]
+    fn test_mint_from_address() {
+        let mint: Mint = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU"
+            .parse()
+            .unwrap();
+
+        assert_eq!(mint.symbol, None);
+        assert_eq!(mint.label(), "7xKX…gAsU");
+        assert!("not-a-mint".parse::<Mint>().is_err());
+    }
+}
@@ -1,4 +1,19 @@
 pub mod account;
+pub mod account_history;
+pub mod address_book;
+pub mod alert;
+pub mod amount;
 pub mod block;
+pub mod commitment;
+pub mod connection;
+pub mod event;
+pub mod filter;
+pub mod mint;
 pub mod program;
+pub mod report;
+pub mod slot_tracker;
+pub mod stats;
+pub mod trace;
 pub mod transaction;
+pub mod transaction_detail;
+pub mod transfer_graph;
@@ -1,14 +1,63 @@
-#[derive(Debug, Clone)]
+use serde::Serialize;
+
+pub const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PazV3Y6ZKm7k1aM";
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgramKind {
+    System,
+    Token,
+    #[serde(rename = "token_2022")]
+    Token2022,
+    Other,
+}
+
+impl ProgramKind {
+    pub fn from_address(address: &str) -> Self {
+        match address {
+            SYSTEM_PROGRAM_ID => ProgramKind::System,
+            TOKEN_PROGRAM_ID => ProgramKind::Token,
+            TOKEN_2022_PROGRAM_ID => ProgramKind::Token2022,
+            _ => ProgramKind::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct Program {
     pub address: String,
     pub index: u8,
+    pub kind: ProgramKind,
 }
 
 impl Program {
     pub fn new<U: ToString>(address: U, index: u8) -> Self {
+        let address = address.to_string();
+        let kind = ProgramKind::from_address(&address);
+
         Self {
-            address: address.to_string(),
+            address,
             index,
+            kind,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_program_kind() {
+        assert_eq!(Program::new(TOKEN_PROGRAM_ID, 1).kind, ProgramKind::Token);
+        assert_eq!(
+            Program::new(TOKEN_2022_PROGRAM_ID, 1).kind,
+            ProgramKind::Token2022
+        );
+        assert_eq!(Program::new(SYSTEM_PROGRAM_ID, 0).kind, ProgramKind::System);
+        assert_eq!(Program::new("program", 1).kind, ProgramKind::Other);
+    }
+}
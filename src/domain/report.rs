@@ -0,0 +1,36 @@
+use crate::domain::amount::{Amount, Volume};
+use crate::domain::mint::Mint;
+use serde::Serialize;
+
+// Rows of the canned reports over stored transfers. Volumes only add up
+// transfers of the same mint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SenderTotal {
+    pub address: String,
+    pub token: Mint,
+    pub transfers: u64,
+    pub volume: Volume,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HourlyVolume {
+    // Unix timestamp of the start of the hour.
+    pub hour: i64,
+    pub token: Mint,
+    pub transfers: u64,
+    pub volume: Volume,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StoredTransfer {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub signature: String,
+    pub instruction_index: usize,
+    pub source: String,
+    pub destination: String,
+    pub program: String,
+    pub token: Mint,
+    pub amount: Amount,
+    pub fee: Option<Amount>,
+}
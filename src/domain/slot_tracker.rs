@@ -0,0 +1,373 @@
+use crate::domain::block::Block;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{Duration, Instant};
+
+// Retries of a failed slot wait `initial`, then twice as long each time up
+// to `max`, and stop after `attempts` fetches in all.
+#[derive(Debug, Clone, Copy)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+    pub attempts: u32,
+}
+
+impl Backoff {
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+
+        self.initial.saturating_mul(factor).min(self.max)
+    }
+}
+
+#[derive(Debug)]
+enum SlotState {
+    Pending { attempts: u32, retry_at: Instant },
+    Fetched(Block),
+    Skipped,
+    GaveUp { attempts: u32, error: String },
+}
+
+// A slot whose block could not be fetched, even after retrying.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MissedSlot {
+    pub slot: u64,
+    pub attempts: u32,
+    pub error: String,
+}
+
+#[derive(Debug)]
+pub enum SlotOutcome {
+    Block(Block),
+    // The slot has no block, its leader did not produce one.
+    Skipped(u64),
+    GaveUp(MissedSlot),
+}
+
+// Follows every slot from the first one seen, or from the start slot, so that
+// none goes missing: slots that never showed up as completed between two that
+// did are fetched as well, failed fetches are retried, and outcomes come out
+// in slot order once every earlier slot is settled. Slots of a gap are only
+// queued as they become due, so a long one does not fill up memory.
+pub struct SlotTracker {
+    backoff: Backoff,
+    slots: BTreeMap<u64, SlotState>,
+    // The pending slots, by when they are due.
+    retries: BTreeSet<(Instant, u64)>,
+    // The lowest slot whose outcome has not come out yet.
+    next_slot: Option<u64>,
+    // The lowest slot not queued yet, up to the highest one.
+    unqueued_slot: Option<u64>,
+    highest_slot: Option<u64>,
+}
+
+impl SlotTracker {
+    pub fn new(start_slot: Option<u64>, backoff: Backoff) -> Self {
+        Self {
+            backoff,
+            slots: BTreeMap::new(),
+            retries: BTreeSet::new(),
+            next_slot: start_slot,
+            unqueued_slot: None,
+            highest_slot: None,
+        }
+    }
+
+    // A slot completed. Slots between it and the highest one so far are
+    // fetched too, their notifications may have been lost.
+    pub fn complete(&mut self, slot: u64) {
+        let next_slot = *self.next_slot.get_or_insert(slot);
+        if slot < next_slot {
+            return;
+        }
+
+        self.unqueued_slot.get_or_insert(next_slot);
+        self.highest_slot = self.highest_slot.max(Some(slot));
+    }
+
+    // Slots to fetch now, at most the given number of them: retries first,
+    // then the lowest slots not fetched yet.
+    pub fn due(&mut self, now: Instant, limit: usize) -> Vec<u64> {
+        let mut due: Vec<u64> = self
+            .retries
+            .iter()
+            .take_while(|(retry_at, _)| *retry_at <= now)
+            .map(|(_, slot)| *slot)
+            .take(limit)
+            .collect();
+
+        if let (Some(unqueued_slot), Some(highest_slot)) = (self.unqueued_slot, self.highest_slot) {
+            let queued: Vec<u64> = (unqueued_slot..=highest_slot)
+                .take(limit - due.len())
+                .collect();
+            for slot in &queued {
+                self.update(
+                    *slot,
+                    SlotState::Pending {
+                        attempts: 0,
+                        retry_at: now,
+                    },
+                );
+            }
+            self.unqueued_slot = Some(unqueued_slot + queued.len() as u64);
+            due.extend(queued);
+        }
+
+        due
+    }
+
+    // When the next retry is due, if any is waiting.
+    pub fn next_retry(&self) -> Option<Instant> {
+        self.retries.first().map(|(retry_at, _)| *retry_at)
+    }
+
+    pub fn fetched(&mut self, block: Block) {
+        if self.slots.contains_key(&block.slot) {
+            self.update(block.slot, SlotState::Fetched(block));
+        }
+    }
+
+    pub fn skipped(&mut self, slot: u64) {
+        if self.slots.contains_key(&slot) {
+            self.update(slot, SlotState::Skipped);
+        }
+    }
+
+    pub fn failed(&mut self, slot: u64, error: String, now: Instant) {
+        let Some(SlotState::Pending { attempts, .. }) = self.slots.get(&slot) else {
+            return;
+        };
+        let attempts = attempts + 1;
+
+        let state = if attempts >= self.backoff.attempts {
+            SlotState::GaveUp { attempts, error }
+        } else {
+            SlotState::Pending {
+                attempts,
+                retry_at: now + self.backoff.delay(attempts),
+            }
+        };
+        self.update(slot, state);
+    }
+
+    // A slot whose block was dropped once another block was finalized in it,
+    // to be fetched again at finalized commitment.
+    pub fn refetch(&mut self, slot: u64, now: Instant) {
+        if self.is_refetch(slot) {
+            self.update(
+                slot,
+                SlotState::Pending {
+                    attempts: 0,
+                    retry_at: now,
+                },
+            );
+        }
+    }
+
+    // Whether the slot is fetched again, its outcome already came out once.
+    pub fn is_refetch(&self, slot: u64) -> bool {
+        self.next_slot.is_some_and(|next_slot| slot < next_slot)
+    }
+
+    // The outcomes of the settled slots up to the first one still pending.
+    // Slots fetched again come out first, as soon as they settle.
+    pub fn pop_settled(&mut self) -> Vec<SlotOutcome> {
+        let mut outcomes = Vec::new();
+        let next_slot = self.next_slot.unwrap_or(0);
+
+        let refetched: Vec<u64> = self
+            .slots
+            .range(..next_slot)
+            .filter(|(_, state)| !matches!(state, SlotState::Pending { .. }))
+            .map(|(slot, _)| *slot)
+            .collect();
+        for slot in refetched {
+            if let Some(state) = self.slots.remove(&slot) {
+                outcomes.push(outcome(slot, state));
+            }
+        }
+
+        while let Some((&slot, state)) = self.slots.range(next_slot..).next() {
+            if matches!(state, SlotState::Pending { .. }) {
+                break;
+            }
+
+            let state = self.slots.remove(&slot).unwrap();
+            self.next_slot = Some(slot + 1);
+            outcomes.push(outcome(slot, state));
+        }
+
+        outcomes
+    }
+
+    // Sets the state of a slot, keeping the retries in step.
+    fn update(&mut self, slot: u64, state: SlotState) {
+        let retry_at = match state {
+            SlotState::Pending { retry_at, .. } => Some(retry_at),
+            _ => None,
+        };
+        if let Some(SlotState::Pending { retry_at, .. }) = self.slots.insert(slot, state) {
+            self.retries.remove(&(retry_at, slot));
+        }
+        if let Some(retry_at) = retry_at {
+            self.retries.insert((retry_at, slot));
+        }
+    }
+}
+
+fn outcome(slot: u64, state: SlotState) -> SlotOutcome {
+    match state {
+        SlotState::Fetched(block) => SlotOutcome::Block(block),
+        SlotState::Skipped => SlotOutcome::Skipped(slot),
+        SlotState::GaveUp { attempts, error } => SlotOutcome::GaveUp(MissedSlot {
+            slot,
+            attempts,
+            error,
+        }),
+        SlotState::Pending { .. } => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BACKOFF: Backoff = Backoff {
+        initial: Duration::from_secs(1),
+        max: Duration::from_secs(4),
+        attempts: 3,
+    };
+
+    fn block(slot: u64) -> Block {
+        Block::new(slot, format!("hash-{}", slot))
+    }
+
+    fn slots(outcomes: &[SlotOutcome]) -> Vec<String> {
+        outcomes
+            .iter()
+            .map(|outcome| match outcome {
+                SlotOutcome::Block(block) => format!("block {}", block.slot),
+                SlotOutcome::Skipped(slot) => format!("skipped {}", slot),
+                SlotOutcome::GaveUp(missed) => {
+                    format!("gave up {} after {}", missed.slot, missed.attempts)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_backoff_delay() {
+        assert_eq!(BACKOFF.delay(1), Duration::from_secs(1));
+        assert_eq!(BACKOFF.delay(2), Duration::from_secs(2));
+        assert_eq!(BACKOFF.delay(3), Duration::from_secs(4));
+        assert_eq!(BACKOFF.delay(40), Duration::from_secs(4));
+    }
+
+    #[test]
+    fn test_gaps_and_order() {
+        let now = Instant::now();
+        let mut tracker = SlotTracker::new(None, BACKOFF);
+
+        tracker.complete(10);
+        tracker.complete(13);
+        // Late and duplicate notifications change nothing.
+        tracker.complete(12);
+        tracker.complete(13);
+        assert_eq!(tracker.due(now, 10), vec![10, 11, 12, 13]);
+
+        tracker.fetched(block(13));
+        tracker.fetched(block(10));
+        tracker.skipped(11);
+        assert_eq!(slots(&tracker.pop_settled()), ["block 10", "skipped 11"]);
+
+        tracker.fetched(block(12));
+        assert_eq!(slots(&tracker.pop_settled()), ["block 12", "block 13"]);
+
+        tracker.complete(9);
+        assert!(tracker.due(now, 10).is_empty());
+    }
+
+    #[test]
+    fn test_start_slot() {
+        let now = Instant::now();
+        let mut tracker = SlotTracker::new(Some(5), BACKOFF);
+
+        tracker.complete(3);
+        tracker.complete(7);
+        assert_eq!(tracker.due(now, 10), vec![5, 6, 7]);
+        assert_eq!(tracker.due(now, 2), vec![5, 6]);
+    }
+
+    #[test]
+    fn test_long_gap() {
+        let now = Instant::now();
+        let mut tracker = SlotTracker::new(Some(1), BACKOFF);
+
+        tracker.complete(1_000_000);
+        assert_eq!(tracker.due(now, 3), vec![1, 2, 3]);
+        assert_eq!(tracker.slots.len(), 3);
+
+        tracker.fetched(block(1));
+        tracker.failed(2, "timeout".to_string(), now);
+        assert_eq!(tracker.due(now, 3), vec![3, 4, 5]);
+        assert_eq!(slots(&tracker.pop_settled()), ["block 1"]);
+
+        let later = now + Duration::from_secs(1);
+        assert_eq!(tracker.due(later, 4), vec![3, 4, 5, 2]);
+        assert_eq!(tracker.next_retry(), Some(now));
+    }
+
+    #[test]
+    fn test_retries_and_give_up() {
+        let now = Instant::now();
+        let mut tracker = SlotTracker::new(None, BACKOFF);
+
+        tracker.complete(1);
+        tracker.complete(2);
+        assert_eq!(tracker.due(now, 10), vec![1, 2]);
+        tracker.fetched(block(2));
+        tracker.failed(1, "timeout".to_string(), now);
+        assert!(tracker.pop_settled().is_empty());
+        assert!(tracker.due(now, 10).is_empty());
+        assert_eq!(tracker.next_retry(), Some(now + Duration::from_secs(1)));
+
+        let later = now + Duration::from_secs(1);
+        assert_eq!(tracker.due(later, 10), vec![1]);
+        tracker.failed(1, "timeout".to_string(), later);
+        assert_eq!(tracker.next_retry(), Some(later + Duration::from_secs(2)));
+        tracker.failed(1, "timeout".to_string(), later);
+
+        assert_eq!(
+            slots(&tracker.pop_settled()),
+            ["gave up 1 after 3", "block 2"]
+        );
+        assert_eq!(tracker.next_retry(), None);
+    }
+
+    #[test]
+    fn test_refetch() {
+        let now = Instant::now();
+        let mut tracker = SlotTracker::new(None, BACKOFF);
+
+        tracker.complete(1);
+        assert_eq!(tracker.due(now, 10), vec![1]);
+        tracker.fetched(block(1));
+        assert_eq!(slots(&tracker.pop_settled()), ["block 1"]);
+
+        // Slots not output yet are not fetched again.
+        tracker.refetch(2, now);
+        assert!(!tracker.is_refetch(2));
+        assert!(tracker.due(now, 10).is_empty());
+
+        tracker.refetch(1, now);
+        tracker.complete(3);
+        assert!(tracker.is_refetch(1));
+        assert_eq!(tracker.due(now, 10), vec![1, 2, 3]);
+
+        // A slot fetched again does not hold back the later ones.
+        tracker.fetched(block(2));
+        assert_eq!(slots(&tracker.pop_settled()), ["block 2"]);
+        tracker.fetched(block(1));
+        assert_eq!(slots(&tracker.pop_settled()), ["block 1"]);
+    }
+}
@@ -0,0 +1,360 @@
+use crate::domain::amount::{Amount, Volume};
+use crate::domain::block::Block;
+use crate::domain::mint::Mint;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+
+// The rolling windows reported by default: one minute, five minutes, one hour.
+pub const WINDOWS: [Duration; 3] = [
+    Duration::from_secs(60),
+    Duration::from_secs(300),
+    Duration::from_secs(3600),
+];
+
+struct Entry {
+    time: i64,
+    source: String,
+    destination: String,
+    token: Mint,
+    amount: Amount,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Counterparty {
+    pub address: String,
+    pub amount: Volume,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MintStats {
+    pub token: Mint,
+    pub transfers: usize,
+    pub volume: Volume,
+    pub volume_per_minute: Volume,
+    pub median: Amount,
+    pub p99: Amount,
+    pub top_senders: Vec<Counterparty>,
+    pub top_receivers: Vec<Counterparty>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowStats {
+    pub window_seconds: u64,
+    pub slots: usize,
+    pub transfers: usize,
+    pub transfers_per_slot: f64,
+    pub unique_senders: usize,
+    pub unique_receivers: usize,
+    pub mints: Vec<MintStats>,
+}
+
+// Transfers over the longest window, summarised on demand over any shorter
+// one. Amounts are only added up within a mint. Time is the block time, in
+// seconds, so blocks caught up on or backfilled fall in the windows they were
+// produced in, and the windows end at the latest block.
+pub struct TransferStats {
+    horizon: Duration,
+    entries: VecDeque<Entry>,
+    slots: VecDeque<(i64, u64)>,
+    first_time: Option<i64>,
+    latest_time: Option<i64>,
+}
+
+impl TransferStats {
+    pub fn new(horizon: Duration) -> Self {
+        Self {
+            horizon,
+            entries: VecDeque::new(),
+            slots: VecDeque::new(),
+            first_time: None,
+            latest_time: None,
+        }
+    }
+
+    // Blocks without a block time are placed at the time they were received,
+    // in seconds since the epoch.
+    pub fn record(&mut self, block: &Block, received_at: i64) {
+        let time = block.block_time.unwrap_or(received_at);
+        self.first_time = Some(self.first_time.map_or(time, |first| first.min(time)));
+        self.latest_time = Some(self.latest_time.map_or(time, |latest| latest.max(time)));
+        self.slots.push_back((time, block.slot));
+
+        for transaction in &block.transactions {
+            self.entries.push_back(Entry {
+                time,
+                source: transaction.source.address.clone(),
+                destination: transaction.destination.address.clone(),
+                token: transaction.token.clone(),
+                amount: transaction.amount(),
+            });
+        }
+
+        self.expire();
+    }
+
+    fn expire(&mut self) {
+        let Some(latest_time) = self.latest_time else {
+            return;
+        };
+        let horizon = self.horizon.as_secs() as i64;
+        let expired = |time: &i64| latest_time - *time > horizon;
+
+        while self
+            .entries
+            .front()
+            .is_some_and(|entry| expired(&entry.time))
+        {
+            self.entries.pop_front();
+        }
+        while self.slots.front().is_some_and(|(time, _)| expired(time)) {
+            self.slots.pop_front();
+        }
+    }
+
+    pub fn windows(&self, top: usize) -> Vec<WindowStats> {
+        WINDOWS
+            .iter()
+            .map(|window| self.window(*window, top))
+            .collect()
+    }
+
+    pub fn window(&self, window: Duration, top: usize) -> WindowStats {
+        let latest_time = self.latest_time.unwrap_or(0);
+        let within = |time: &i64| latest_time - *time <= window.as_secs() as i64;
+        let entries: Vec<&Entry> = self
+            .entries
+            .iter()
+            .filter(|entry| within(&entry.time))
+            .collect();
+        let slots = self
+            .slots
+            .iter()
+            .filter(|(time, _)| within(time))
+            .map(|(_, slot)| *slot)
+            .collect::<HashSet<u64>>()
+            .len();
+
+        // Rates are over the part of the window that was observed.
+        let observed = match self.first_time {
+            Some(first_time) => Duration::from_secs((latest_time - first_time).max(0) as u64),
+            None => Duration::ZERO,
+        };
+        let span = window.min(observed);
+
+        let mut by_mint: Vec<(&Mint, Vec<&Entry>)> = Vec::new();
+        for entry in &entries {
+            match by_mint.iter_mut().find(|(token, _)| **token == entry.token) {
+                Some((_, group)) => group.push(entry),
+                None => by_mint.push((&entry.token, vec![entry])),
+            }
+        }
+
+        WindowStats {
+            window_seconds: window.as_secs(),
+            slots,
+            transfers: entries.len(),
+            transfers_per_slot: match slots {
+                0 => 0.0,
+                slots => entries.len() as f64 / slots as f64,
+            },
+            unique_senders: unique(entries.iter().map(|entry| &entry.source)),
+            unique_receivers: unique(entries.iter().map(|entry| &entry.destination)),
+            mints: by_mint
+                .into_iter()
+                .map(|(token, group)| mint_stats(token, &group, span, top))
+                .collect(),
+        }
+    }
+}
+
+fn unique<'a, I: Iterator<Item = &'a String>>(addresses: I) -> usize {
+    addresses.collect::<HashSet<_>>().len()
+}
+
+fn mint_stats(token: &Mint, entries: &[&Entry], span: Duration, top: usize) -> MintStats {
+    let decimals = entries[0].amount.decimals;
+    let mut raws: Vec<u64> = entries.iter().map(|entry| entry.amount.raw).collect();
+    raws.sort_unstable();
+
+    let volume: u128 = raws.iter().map(|raw| *raw as u128).sum();
+    let per_minute = volume * 60 / span.as_secs().max(1) as u128;
+
+    MintStats {
+        token: token.clone(),
+        transfers: raws.len(),
+        volume: Volume::new(volume, decimals),
+        volume_per_minute: Volume::new(per_minute, decimals),
+        median: Amount::new(percentile(&raws, 50), decimals),
+        p99: Amount::new(percentile(&raws, 99), decimals),
+        top_senders: counterparties(
+            entries
+                .iter()
+                .map(|entry| (&entry.source, entry.amount.raw)),
+            decimals,
+            top,
+        ),
+        top_receivers: counterparties(
+            entries
+                .iter()
+                .map(|entry| (&entry.destination, entry.amount.raw)),
+            decimals,
+            top,
+        ),
+    }
+}
+
+// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[u64], percent: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (percent * sorted.len()).div_ceil(100).max(1);
+
+    sorted[rank - 1]
+}
+
+fn counterparties<'a, I: Iterator<Item = (&'a String, u64)>>(
+    flows: I,
+    decimals: u8,
+    top: usize,
+) -> Vec<Counterparty> {
+    let mut totals: HashMap<&String, Volume> = HashMap::new();
+    for (address, raw) in flows {
+        totals
+            .entry(address)
+            .or_insert_with(|| Volume::new(0, decimals))
+            .add(Amount::new(raw, decimals));
+    }
+
+    let mut totals: Vec<Counterparty> = totals
+        .into_iter()
+        .map(|(address, amount)| Counterparty {
+            address: address.clone(),
+            amount,
+        })
+        .collect();
+    totals.sort_by(|a, b| {
+        b.amount
+            .raw
+            .cmp(&a.amount.raw)
+            .then_with(|| a.address.cmp(&b.address))
+    });
+    totals.truncate(top);
+
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::program::Program;
+    use crate::domain::transaction::Transaction;
+
+    fn block(slot: u64, transfers: &[(&str, &str, u64)]) -> Block {
+        let mut block = Block::new(slot, "hash".to_string());
+        for (source, destination, amount) in transfers {
+            block.add_transaction(Transaction::new(
+                "sig".to_string(),
+                Account::new(*source, 1, Amount::zero(6)),
+                Account::new(*destination, 2, Amount::zero(6)),
+                Program::new("program", 3),
+                "USDC".parse::<Mint>().unwrap(),
+                Amount::new(*amount, 6),
+            ));
+        }
+
+        block
+    }
+
+    #[test]
+    fn test_percentile() {
+        let values: Vec<u64> = (1..=100).collect();
+
+        assert_eq!(percentile(&values, 50), 50);
+        assert_eq!(percentile(&values, 99), 99);
+        assert_eq!(percentile(&[7], 99), 7);
+        assert_eq!(percentile(&[1, 2, 3], 50), 2);
+        assert_eq!(percentile(&[], 50), 0);
+    }
+
+    #[test]
+    fn test_windows() {
+        let start = 1_700_000_000;
+        let mut stats = TransferStats::new(Duration::from_secs(3600));
+
+        stats.record(&block(1, &[("alice", "bob", 1_000_000)]), start);
+        stats.record(&block(2, &[]), start + 200);
+        stats.record(
+            &block(
+                3,
+                &[("alice", "carol", 3_000_000), ("dave", "carol", 2_000_000)],
+            ),
+            start + 290,
+        );
+        stats.record(&block(4, &[]), start + 300);
+
+        let minute = stats.window(Duration::from_secs(60), 5);
+        assert_eq!(minute.slots, 2);
+        assert_eq!(minute.transfers, 2);
+        assert_eq!(minute.transfers_per_slot, 1.0);
+        assert_eq!(minute.unique_senders, 2);
+        assert_eq!(minute.unique_receivers, 1);
+        assert_eq!(minute.mints[0].volume, Volume::new(5_000_000, 6));
+        assert_eq!(minute.mints[0].median, Amount::new(2_000_000, 6));
+        assert_eq!(minute.mints[0].p99, Amount::new(3_000_000, 6));
+
+        let five = stats.window(Duration::from_secs(300), 1);
+        assert_eq!(five.slots, 4);
+        assert_eq!(five.transfers, 3);
+        assert_eq!(five.mints[0].volume_per_minute, Volume::new(1_200_000, 6));
+        assert_eq!(
+            five.mints[0].top_senders,
+            vec![Counterparty {
+                address: "alice".to_string(),
+                amount: Volume::new(4_000_000, 6),
+            }]
+        );
+
+        // Only five minutes were observed of the hour.
+        let hour = stats.window(Duration::from_secs(3600), 5);
+        assert_eq!(hour.mints[0].volume_per_minute, Volume::new(1_200_000, 6));
+
+        stats.record(&block(5, &[]), start + 3601);
+        assert_eq!(stats.window(Duration::from_secs(3600), 5).transfers, 2);
+    }
+
+    #[test]
+    fn test_block_time() {
+        let received_at = 1_700_000_000;
+        let mut stats = TransferStats::new(Duration::from_secs(3600));
+
+        // Blocks caught up on together keep the times they were produced at.
+        for (slot, block_time) in [(1, received_at - 600), (2, received_at - 30)] {
+            let mut block = block(slot, &[("alice", "bob", 1_000_000)]);
+            block.update_block_time(Some(block_time));
+            stats.record(&block, received_at);
+        }
+
+        assert_eq!(stats.window(Duration::from_secs(60), 5).transfers, 1);
+        assert_eq!(stats.window(Duration::from_secs(3600), 5).transfers, 2);
+    }
+
+    #[test]
+    fn test_volume_past_u64() {
+        let mut stats = TransferStats::new(Duration::from_secs(3600));
+        stats.record(
+            &block(
+                1,
+                &[("alice", "bob", u64::MAX), ("alice", "carol", u64::MAX)],
+            ),
+            1_700_000_000,
+        );
+
+        let expected = Volume::new(2 * u64::MAX as u128, 6);
+        let window = stats.window(Duration::from_secs(60), 1);
+        assert_eq!(window.mints[0].volume, expected);
+        assert_eq!(window.mints[0].top_senders[0].amount, expected);
+    }
+}
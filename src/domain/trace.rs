@@ -0,0 +1,124 @@
+use crate::domain::amount::Amount;
+use crate::domain::transaction::Transaction;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceDirection {
+    // Where the funds went.
+    Downstream,
+    // Where the funds came from.
+    Upstream,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StopReason {
+    // A known exchange, program or any address the trace was told to stop at.
+    Known,
+    // The address already appears higher up in the same branch.
+    Cycle,
+    // The history of the address could not be fetched, the branch is incomplete.
+    Unavailable(String),
+}
+
+// One hop of a trace. The transaction is the transfer that led to the address,
+// with its amount attenuated to the share of the traced funds it can carry.
+// The root has no transaction and the slot the trace starts from.
+#[derive(Debug, Clone)]
+pub struct TraceNode {
+    pub address: String,
+    pub slot: u64,
+    pub transaction: Option<Transaction>,
+    pub stop: Option<StopReason>,
+    pub children: Vec<TraceNode>,
+}
+
+impl TraceNode {
+    pub fn new<U: ToString>(address: U, slot: u64, transaction: Option<Transaction>) -> Self {
+        Self {
+            address: address.to_string(),
+            slot,
+            transaction,
+            stop: None,
+            children: Vec::new(),
+        }
+    }
+
+    pub fn update_stop(&mut self, stop: StopReason) {
+        self.stop = Some(stop);
+    }
+
+    pub fn add_child(&mut self, child: TraceNode) {
+        self.children.push(child);
+    }
+
+    // Every attenuated transfer of the tree with its slot, parents before their children.
+    pub fn transactions(&self) -> Vec<(u64, &Transaction)> {
+        let mut transactions: Vec<(u64, &Transaction)> = self
+            .transaction
+            .iter()
+            .map(|transaction| (self.slot, transaction))
+            .collect();
+
+        for child in &self.children {
+            transactions.append(&mut child.transactions());
+        }
+
+        transactions
+    }
+}
+
+// Splits the traced funds over the transfers, taken in order: each transfer
+// carries at most what is left, so once the budget is spent the remaining
+// transfers carry nothing. Without a budget every transfer is followed in full.
+pub fn attenuate(budget: Option<Amount>, amounts: &[Amount]) -> Vec<Amount> {
+    let Some(mut left) = budget else {
+        return amounts.to_vec();
+    };
+
+    amounts
+        .iter()
+        .map(|amount| {
+            let carried = if amount.raw < left.raw { *amount } else { left };
+            left = left.saturating_sub(carried);
+            carried
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::mint::Mint;
+    use crate::domain::program::Program;
+
+    #[test]
+    fn test_attenuate() {
+        let amounts = [Amount::new(40, 6), Amount::new(50, 6), Amount::new(30, 6)];
+
+        assert_eq!(attenuate(None, &amounts), amounts.to_vec());
+        assert_eq!(
+            attenuate(Some(Amount::new(70, 6)), &amounts),
+            vec![Amount::new(40, 6), Amount::new(30, 6), Amount::new(0, 6)]
+        );
+    }
+
+    #[test]
+    fn test_transactions() {
+        let transaction = Transaction::new(
+            "signature".to_string(),
+            Account::new("alice", 1, Amount::zero(6)),
+            Account::new("bob", 2, Amount::zero(6)),
+            Program::new("program", 3),
+            Mint::new("mint"),
+            Amount::new(10, 6),
+        );
+        let mut root = TraceNode::new("alice", 1, None);
+        let mut child = TraceNode::new("bob", 2, Some(transaction.clone()));
+        child.add_child(TraceNode::new("carol", 3, Some(transaction)));
+        root.add_child(child);
+
+        let transactions = root.transactions();
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[1].0, 3);
+    }
+}
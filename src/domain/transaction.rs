@@ -1,13 +1,21 @@
 use crate::domain::account::Account;
+use crate::domain::amount::Amount;
+use crate::domain::mint::Mint;
 use crate::domain::program::Program;
+use serde::Serialize;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Transaction {
     pub signature: String,
     pub source: Account,
     pub destination: Account,
     pub program: Program,
-    pub token: String,
+    pub token: Mint,
+    pub amount: Amount,
+    pub fee: Option<Amount>,
+    // Position of the instruction that made the transfer among the outer
+    // instructions followed by the inner ones, unique within the signature.
+    pub instruction_index: usize,
 }
 
 impl Transaction {
@@ -16,49 +24,71 @@ impl Transaction {
         source: Account,
         destination: Account,
         program: Program,
-        token: String,
+        token: Mint,
+        amount: Amount,
     ) -> Self {
         Self {
             signature: signature.to_string(),
             source,
             destination,
             program,
-            token: token.to_string(),
+            token,
+            amount,
+            fee: None,
+            instruction_index: 0,
         }
     }
 
-    pub fn amount(&self) -> f64 {
-        self.destination.post_balance - self.destination.pre_balance
+    pub fn amount(&self) -> Amount {
+        self.amount
+    }
+
+    pub fn update_fee(&mut self, fee: Amount) {
+        self.fee = Some(fee);
+    }
+
+    pub fn update_instruction_index(&mut self, instruction_index: usize) {
+        self.instruction_index = instruction_index;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::program::ProgramKind;
 
     #[test]
     fn test_transaction_amount() {
         let source = Account {
             address: "source".to_string(),
             index: 0,
-            pre_balance: 100.0,
-            post_balance: 50.0,
+            pre_balance: Amount::new(100_000_000, 6),
+            post_balance: Amount::new(50_000_000, 6),
         };
         let destination = Account {
             address: "destination".to_string(),
             index: 1,
-            pre_balance: 100.0,
-            post_balance: 150.0,
+            pre_balance: Amount::new(100_000_000, 6),
+            post_balance: Amount::new(150_000_000, 6),
         };
         let program = Program {
             address: "program".to_string(),
             index: 2,
+            kind: ProgramKind::Other,
         };
 
-        let token = "USDC".to_string();
-        let transaction =
-            Transaction::new("signature".to_string(), source, destination, program, token);
+        let token = Mint::new("mint");
+        let amount = Amount::new(50_000_000, 6);
+        let transaction = Transaction::new(
+            "signature".to_string(),
+            source,
+            destination,
+            program,
+            token,
+            amount,
+        );
 
-        assert_eq!(transaction.amount(), 50.0);
+        assert_eq!(transaction.amount(), Amount::new(50_000_000, 6));
+        assert_eq!(transaction.amount().to_string(), "50");
     }
 }
@@ -0,0 +1,103 @@
+use crate::domain::amount::Amount;
+use crate::domain::event::{Event, EventKind};
+use crate::domain::transaction::Transaction;
+
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    pub program: String,
+    pub accounts: Vec<String>,
+    pub data: String,
+    pub stack_height: u32,
+    pub event: Option<EventKind>,
+    pub inner_instructions: Vec<Instruction>,
+}
+
+impl Instruction {
+    pub fn new<U: ToString>(
+        program: U,
+        accounts: Vec<String>,
+        data: U,
+        stack_height: u32,
+        event: Option<EventKind>,
+    ) -> Self {
+        Self {
+            program: program.to_string(),
+            accounts,
+            data: data.to_string(),
+            stack_height,
+            event,
+            inner_instructions: Vec::new(),
+        }
+    }
+
+    // Nests a CPI under the deepest instruction that invoked it, using the
+    // stack height reported by the runtime (1 for top-level instructions).
+    pub fn add_inner_instruction(&mut self, instruction: Instruction) {
+        if let Some(last) = self.inner_instructions.last_mut() {
+            if instruction.stack_height > last.stack_height {
+                last.add_inner_instruction(instruction);
+                return;
+            }
+        }
+
+        self.inner_instructions.push(instruction);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TransactionDetail {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub fee_payer: String,
+    pub fee: Amount,
+    pub compute_units: Option<u64>,
+    pub error: Option<String>,
+    pub logs: Vec<String>,
+    pub transactions: Vec<Transaction>,
+    pub events: Vec<Event>,
+    pub instructions: Vec<Instruction>,
+}
+
+impl TransactionDetail {
+    pub fn new(signature: String, slot: u64, fee_payer: String, fee: Amount) -> Self {
+        Self {
+            signature,
+            slot,
+            block_time: None,
+            fee_payer,
+            fee,
+            compute_units: None,
+            error: None,
+            logs: Vec::new(),
+            transactions: Vec::new(),
+            events: Vec::new(),
+            instructions: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_inner_instruction_builds_cpi_tree() {
+        let mut instruction = Instruction::new("router", Vec::new(), "", 1, None);
+
+        instruction.add_inner_instruction(Instruction::new("amm", Vec::new(), "", 2, None));
+        instruction.add_inner_instruction(Instruction::new("token", Vec::new(), "", 3, None));
+        instruction.add_inner_instruction(Instruction::new("token", Vec::new(), "", 3, None));
+        instruction.add_inner_instruction(Instruction::new("token", Vec::new(), "", 2, None));
+
+        assert_eq!(instruction.inner_instructions.len(), 2);
+        assert_eq!(instruction.inner_instructions[0].program, "amm");
+        assert_eq!(
+            instruction.inner_instructions[0].inner_instructions.len(),
+            2
+        );
+        assert!(instruction.inner_instructions[1]
+            .inner_instructions
+            .is_empty());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::domain::amount::Amount;
+use crate::domain::mint::Mint;
+use crate::domain::transaction::Transaction;
+use std::collections::{HashMap, HashSet};
+
+// Transfers between two owners in one mint. Amounts of different mints are
+// never summed, so a pair of owners gets one edge per mint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edge {
+    pub source: String,
+    pub destination: String,
+    pub token: Mint,
+    pub amount: Amount,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub address: String,
+    pub volume: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TransferGraph {
+    pub edges: Vec<Edge>,
+    merge_parallel: bool,
+}
+
+impl TransferGraph {
+    pub fn new(merge_parallel: bool) -> Self {
+        Self {
+            edges: Vec::new(),
+            merge_parallel,
+        }
+    }
+
+    pub fn add_transaction(&mut self, transaction: &Transaction) {
+        let source = &transaction.source.address;
+        let destination = &transaction.destination.address;
+        let amount = transaction.amount();
+
+        if self.merge_parallel {
+            let parallel = self.edges.iter_mut().find(|edge| {
+                edge.source == *source
+                    && edge.destination == *destination
+                    && edge.token == transaction.token
+            });
+
+            if let Some(edge) = parallel {
+                edge.amount = edge
+                    .amount
+                    .checked_add(amount)
+                    .unwrap_or(Amount::new(u64::MAX, amount.decimals));
+                edge.count += 1;
+                return;
+            }
+        }
+
+        self.edges.push(Edge {
+            source: source.clone(),
+            destination: destination.clone(),
+            token: transaction.token.clone(),
+            amount,
+            count: 1,
+        });
+    }
+
+    // Nodes ordered by the UI amount flowing in and out of them, largest first.
+    // Volumes of different mints are added as is, which is only good for ranking.
+    pub fn nodes(&self) -> Vec<Node> {
+        let mut volumes: HashMap<&str, f64> = HashMap::new();
+
+        for edge in &self.edges {
+            let amount = edge.amount.to_f64();
+            *volumes.entry(&edge.source).or_default() += amount;
+            *volumes.entry(&edge.destination).or_default() += amount;
+        }
+
+        let mut nodes: Vec<Node> = volumes
+            .into_iter()
+            .map(|(address, volume)| Node {
+                address: address.to_string(),
+                volume,
+            })
+            .collect();
+        nodes.sort_by(|a, b| {
+            b.volume
+                .total_cmp(&a.volume)
+                .then_with(|| a.address.cmp(&b.address))
+        });
+
+        nodes
+    }
+
+    // Drops edges whose UI amount is below the threshold.
+    pub fn without_dust(mut self, threshold: f64) -> Self {
+        self.edges.retain(|edge| edge.amount.to_f64() >= threshold);
+        self
+    }
+
+    // Keeps the edges between the `limit` nodes with the largest volume.
+    pub fn top_nodes(mut self, limit: usize) -> Self {
+        let top: HashSet<String> = self
+            .nodes()
+            .into_iter()
+            .take(limit)
+            .map(|node| node.address)
+            .collect();
+
+        self.edges
+            .retain(|edge| top.contains(&edge.source) && top.contains(&edge.destination));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::program::Program;
+
+    fn transfer(source: &str, destination: &str, amount: u64) -> Transaction {
+        Transaction::new(
+            "signature".to_string(),
+            Account::new(source, 1, Amount::zero(6)),
+            Account::new(destination, 2, Amount::zero(6)),
+            Program::new("program", 3),
+            Mint::new("mint"),
+            Amount::new(amount, 6),
+        )
+    }
+
+    fn graph(merge_parallel: bool) -> TransferGraph {
+        let mut graph = TransferGraph::new(merge_parallel);
+
+        graph.add_transaction(&transfer("alice", "bob", 1_000_000));
+        graph.add_transaction(&transfer("alice", "bob", 2_000_000));
+        graph.add_transaction(&transfer("bob", "carol", 500_000));
+        graph.add_transaction(&transfer("dave", "carol", 10));
+
+        graph
+    }
+
+    #[test]
+    fn test_merge_parallel_edges() {
+        assert_eq!(graph(false).edges.len(), 4);
+
+        let graph = graph(true);
+        assert_eq!(graph.edges.len(), 3);
+        assert_eq!(graph.edges[0].amount, Amount::new(3_000_000, 6));
+        assert_eq!(graph.edges[0].count, 2);
+    }
+
+    #[test]
+    fn test_dust_and_top_nodes() {
+        let graph = graph(true).without_dust(0.01);
+        assert_eq!(graph.edges.len(), 2);
+
+        let nodes = graph.nodes();
+        assert_eq!(nodes[0].address, "bob");
+        assert_eq!(nodes[0].volume, 3.5);
+
+        let graph = graph.top_nodes(2);
+        assert_eq!(graph.edges.len(), 1);
+        assert_eq!(graph.edges[0].source, "alice");
+    }
+}
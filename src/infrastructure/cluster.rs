@@ -0,0 +1,148 @@
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cluster {
+    MainnetBeta,
+    Devnet,
+    Testnet,
+    Localnet,
+}
+
+impl Cluster {
+    pub fn rpc_url(&self) -> &'static str {
+        match self {
+            Cluster::MainnetBeta => "https://api.mainnet-beta.solana.com",
+            Cluster::Devnet => "https://api.devnet.solana.com",
+            Cluster::Testnet => "https://api.testnet.solana.com",
+            Cluster::Localnet => "http://127.0.0.1:8899",
+        }
+    }
+
+    pub fn websocket_url(&self) -> &'static str {
+        match self {
+            Cluster::MainnetBeta => "wss://api.mainnet-beta.solana.com/",
+            Cluster::Devnet => "wss://api.devnet.solana.com/",
+            Cluster::Testnet => "wss://api.testnet.solana.com/",
+            Cluster::Localnet => "ws://127.0.0.1:8900",
+        }
+    }
+}
+
+impl FromStr for Cluster {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "mainnet-beta" => Ok(Cluster::MainnetBeta),
+            "devnet" => Ok(Cluster::Devnet),
+            "testnet" => Ok(Cluster::Testnet),
+            "localnet" => Ok(Cluster::Localnet),
+            _ => Err(format!(
+                "unknown cluster {:?}, expected one of mainnet-beta, devnet, testnet, localnet",
+                value
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoints {
+    pub rpc_url: String,
+    pub websocket_url: String,
+}
+
+impl Endpoints {
+    // Explicit URLs win over the cluster moniker. When only the RPC URL is given,
+    // the WebSocket URL is derived from it the same way the Solana CLI does it.
+    pub fn resolve(
+        cluster: Cluster,
+        rpc_url: Option<String>,
+        websocket_url: Option<String>,
+    ) -> Self {
+        match (rpc_url, websocket_url) {
+            (Some(rpc_url), Some(websocket_url)) => Self {
+                rpc_url,
+                websocket_url,
+            },
+            (Some(rpc_url), None) => Self {
+                websocket_url: websocket_url_from_rpc_url(&rpc_url),
+                rpc_url,
+            },
+            (None, Some(websocket_url)) => Self {
+                rpc_url: cluster.rpc_url().to_string(),
+                websocket_url,
+            },
+            (None, None) => Self {
+                rpc_url: cluster.rpc_url().to_string(),
+                websocket_url: cluster.websocket_url().to_string(),
+            },
+        }
+    }
+}
+
+fn websocket_url_from_rpc_url(rpc_url: &str) -> String {
+    let (scheme, rest) = match rpc_url.split_once("://") {
+        Some(("https", rest)) => ("wss", rest),
+        Some(("http", rest)) => ("ws", rest),
+        Some((scheme, rest)) => (scheme, rest),
+        None => ("ws", rpc_url),
+    };
+    let (authority, path) = match rest.find('/') {
+        Some(position) => rest.split_at(position),
+        None => (rest, ""),
+    };
+
+    // The PubSub service listens on the port right after the RPC one.
+    let authority = match authority.rsplit_once(':') {
+        Some((host, port)) => match port.parse::<u16>() {
+            Ok(port) => format!("{}:{}", host, port.saturating_add(1)),
+            Err(_) => authority.to_string(),
+        },
+        None => authority.to_string(),
+    };
+
+    format!("{}://{}{}", scheme, authority, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_cluster_moniker() {
+        let endpoints = Endpoints::resolve("devnet".parse().unwrap(), None, None);
+
+        assert_eq!(endpoints.rpc_url, "https://api.devnet.solana.com");
+        assert_eq!(endpoints.websocket_url, "wss://api.devnet.solana.com/");
+        assert!("mainnet".parse::<Cluster>().is_err());
+    }
+
+    #[test]
+    fn test_resolve_explicit_urls() {
+        let endpoints = Endpoints::resolve(
+            Cluster::MainnetBeta,
+            Some("https://rpc.example.com/key".to_string()),
+            Some("wss://ws.example.com/key".to_string()),
+        );
+
+        assert_eq!(endpoints.rpc_url, "https://rpc.example.com/key");
+        assert_eq!(endpoints.websocket_url, "wss://ws.example.com/key");
+    }
+
+    #[test]
+    fn test_resolve_derives_websocket_url() {
+        let endpoints = Endpoints::resolve(
+            Cluster::MainnetBeta,
+            Some("http://127.0.0.1:8899".to_string()),
+            None,
+        );
+        assert_eq!(endpoints.websocket_url, "ws://127.0.0.1:8900");
+
+        let endpoints = Endpoints::resolve(
+            Cluster::MainnetBeta,
+            Some("https://rpc.example.com/key".to_string()),
+            None,
+        );
+        assert_eq!(endpoints.websocket_url, "wss://rpc.example.com/key");
+    }
+}
@@ -0,0 +1,292 @@
+use crate::application::gateways::alert_sink::AlertSink;
+use crate::domain::address_book::AddressBook;
+use crate::domain::alert::{AlertEngine, AlertRule, Burst};
+use crate::domain::filter::{Threshold, TransferFilter};
+use crate::domain::mint::Mint;
+use crate::infrastructure::gateways::alert_sinks::{
+    ExecSink, FileSink, StderrSink, StdoutSink, WebhookSink,
+};
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// Settings read from `--config`, a TOML or JSON file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub filter: FilterConfig,
+    pub alerts: AlertsConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FilterConfig {
+    pub sources: Vec<String>,
+    pub destinations: Vec<String>,
+    pub involves: Vec<String>,
+    pub programs: Vec<String>,
+    pub mints: Vec<String>,
+    pub min_amount: Option<Bound>,
+    pub max_amount: Option<Bound>,
+}
+
+// Amounts may be written as numbers or, to keep every digit, as strings.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Bound {
+    Integer(u64),
+    Float(f64),
+    Text(String),
+}
+
+impl Bound {
+    fn threshold(&self) -> Result<Threshold, String> {
+        match self {
+            Bound::Integer(value) => value.to_string().parse(),
+            Bound::Float(value) => value.to_string().parse(),
+            Bound::Text(value) => value.parse(),
+        }
+    }
+}
+
+impl FilterConfig {
+    pub fn filter(&self) -> Result<TransferFilter> {
+        let threshold = |bound: &Option<Bound>| {
+            bound
+                .as_ref()
+                .map(|bound| bound.threshold().map_err(|e| anyhow!(e)))
+                .transpose()
+        };
+
+        Ok(TransferFilter {
+            sources: self.sources.iter().cloned().collect(),
+            destinations: self.destinations.iter().cloned().collect(),
+            involves: self.involves.iter().cloned().collect(),
+            programs: self.programs.iter().cloned().collect(),
+            mints: self
+                .mints
+                .iter()
+                .map(|mint| mint.parse::<Mint>().map(|mint| mint.address))
+                .collect::<Result<_, _>>()
+                .map_err(|e| anyhow!(e))?,
+            min_amount: threshold(&self.min_amount)?,
+            max_amount: threshold(&self.max_amount)?,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertsConfig {
+    pub dedup_seconds: u64,
+    pub max_per_minute: Option<usize>,
+    pub rules: Vec<RuleConfig>,
+    pub sinks: Vec<SinkConfig>,
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self {
+            dedup_seconds: 300,
+            max_per_minute: None,
+            rules: Vec::new(),
+            sinks: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleConfig {
+    pub name: String,
+    #[serde(default)]
+    pub filter: FilterConfig,
+    pub burst: Option<BurstConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BurstConfig {
+    pub count: usize,
+    pub seconds: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum SinkConfig {
+    Stdout,
+    Stderr,
+    File { path: PathBuf },
+    Exec { command: Vec<String> },
+    Webhook { url: String },
+}
+
+impl AlertsConfig {
+    pub fn engine(&self, address_book: AddressBook) -> Result<AlertEngine> {
+        let rules = self
+            .rules
+            .iter()
+            .map(|rule| {
+                Ok(AlertRule {
+                    name: rule.name.clone(),
+                    filter: rule
+                        .filter
+                        .filter()
+                        .with_context(|| format!("in alert rule {:?}", rule.name))?,
+                    burst: rule.burst.as_ref().map(|burst| Burst {
+                        count: burst.count.max(1),
+                        within: Duration::from_secs(burst.seconds),
+                    }),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(AlertEngine::new(
+            rules,
+            Duration::from_secs(self.dedup_seconds),
+            self.max_per_minute,
+            address_book,
+        ))
+    }
+
+    // Alerts printed on the terminal would draw over the dashboard.
+    pub fn check_dashboard(&self) -> Result<()> {
+        let prints = self.sinks.is_empty()
+            || self
+                .sinks
+                .iter()
+                .any(|sink| matches!(sink, SinkConfig::Stdout | SinkConfig::Stderr));
+
+        if !self.rules.is_empty() && prints {
+            return Err(anyhow!(
+                "alerts can't be printed with --tui, configure a file, exec or webhook sink"
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Alerts go to stderr unless sinks are configured, so that they never mix
+    // with the JSON or CSV written to stdout.
+    pub fn sinks(&self) -> Result<Vec<Box<dyn AlertSink>>> {
+        if self.sinks.is_empty() {
+            return Ok(vec![Box::new(StderrSink)]);
+        }
+
+        self.sinks
+            .iter()
+            .map(|sink| -> Result<Box<dyn AlertSink>> {
+                Ok(match sink {
+                    SinkConfig::Stdout => Box::new(StdoutSink),
+                    SinkConfig::Stderr => Box::new(StderrSink),
+                    SinkConfig::File { path } => Box::new(FileSink::new(path)?),
+                    SinkConfig::Exec { command } => Box::new(ExecSink::new(command.clone())?),
+                    SinkConfig::Webhook { url } => Box::new(WebhookSink::new(url)),
+                })
+            })
+            .collect()
+    }
+}
+
+pub fn load(path: Option<&Path>) -> Result<Config> {
+    let Some(path) = path else {
+        return Ok(Config::default());
+    };
+
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("reading config from {}", path.display()))?;
+
+    parse(&content, path)
+}
+
+// JSON when the file is named `*.json`, TOML otherwise.
+pub fn parse<T: DeserializeOwned>(content: &str, path: &Path) -> Result<T> {
+    let is_json = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+
+    if is_json {
+        serde_json::from_str(content).map_err(|e| anyhow!("invalid {}: {}", path.display(), e))
+    } else {
+        toml::from_str(content).map_err(|e| anyhow!("invalid {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_config() {
+        let toml = r#"
+            [filter]
+            involves = ["7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU"]
+            mints = ["USDC"]
+            min_amount = 1000000
+            max_amount = "2500000.5"
+        "#;
+
+        let config: Config = parse(toml, Path::new("tracker.toml")).unwrap();
+        let filter = config.filter.filter().unwrap();
+
+        assert_eq!(filter.involves.len(), 1);
+        assert!(filter
+            .mints
+            .contains("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"));
+        assert_eq!(filter.min_amount, Some("1000000".parse().unwrap()));
+        assert_eq!(filter.max_amount, Some("2500000.5".parse().unwrap()));
+
+        let config: Config = parse(
+            r#"{ "filter": { "mints": ["nope"] } }"#,
+            Path::new("a.json"),
+        )
+        .unwrap();
+        assert!(config.filter.filter().is_err());
+        assert!(parse::<Config>("[filtre]", Path::new("tracker.toml")).is_err());
+    }
+
+    #[test]
+    fn test_alerts_config() {
+        let toml = r#"
+            [alerts]
+            max_per_minute = 10
+
+            [[alerts.rules]]
+            name = "whale"
+            filter = { min_amount = 1000000 }
+
+            [[alerts.rules]]
+            name = "burst"
+            burst = { count = 5, seconds = 60 }
+
+            [[alerts.sinks]]
+            type = "webhook"
+            url = "http://127.0.0.1:9000/alerts"
+
+            [[alerts.sinks]]
+            type = "exec"
+            command = ["notify-send", "Transfer alert"]
+        "#;
+
+        let config: Config = parse(toml, Path::new("tracker.toml")).unwrap();
+
+        assert_eq!(config.alerts.dedup_seconds, 300);
+        assert_eq!(config.alerts.rules.len(), 2);
+        assert!(!config.alerts.engine(AddressBook::new()).unwrap().is_empty());
+        assert_eq!(config.alerts.sinks().unwrap().len(), 2);
+        assert!(config.alerts.check_dashboard().is_ok());
+        assert!(AlertsConfig {
+            sinks: Vec::new(),
+            ..config.alerts
+        }
+        .check_dashboard()
+        .is_err());
+        assert!(parse::<Config>(
+            "[[alerts.sinks]]\ntype = \"pager\"",
+            Path::new("tracker.toml")
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,4 @@
+pub mod spl_token;
+pub mod spl_token_2022;
+pub mod system;
+pub mod wrapped_sol;
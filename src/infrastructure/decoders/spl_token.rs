@@ -0,0 +1,193 @@
+use crate::domain::event::EventKind;
+
+const TRANSFER: u8 = 3;
+const APPROVE: u8 = 4;
+const REVOKE: u8 = 5;
+const MINT_TO: u8 = 7;
+const BURN: u8 = 8;
+const CLOSE_ACCOUNT: u8 = 9;
+const FREEZE_ACCOUNT: u8 = 10;
+const THAW_ACCOUNT: u8 = 11;
+const TRANSFER_CHECKED: u8 = 12;
+const APPROVE_CHECKED: u8 = 13;
+const MINT_TO_CHECKED: u8 = 14;
+const BURN_CHECKED: u8 = 15;
+const SYNC_NATIVE: u8 = 17;
+
+// Decodes an SPL Token instruction from its raw `data` and the addresses of its
+// accounts, in instruction order. Multisig signers trailing the authority are
+// ignored. Returns `None` for instructions that do not produce an event or that
+// are malformed.
+pub fn decode(data: &[u8], accounts: &[String]) -> Option<EventKind> {
+    let (tag, rest) = data.split_first()?;
+    let account = |position: usize| accounts.get(position).cloned();
+
+    let event = match *tag {
+        TRANSFER => EventKind::Transfer {
+            source: account(0)?,
+            destination: account(1)?,
+            authority: account(2)?,
+            amount: read_u64(rest)?,
+        },
+        APPROVE => EventKind::Approve {
+            source: account(0)?,
+            delegate: account(1)?,
+            owner: account(2)?,
+            amount: read_u64(rest)?,
+        },
+        REVOKE => EventKind::Revoke {
+            source: account(0)?,
+            owner: account(1)?,
+        },
+        MINT_TO => EventKind::MintTo {
+            mint: account(0)?,
+            account: account(1)?,
+            authority: account(2)?,
+            amount: read_u64(rest)?,
+        },
+        BURN => EventKind::Burn {
+            account: account(0)?,
+            mint: account(1)?,
+            authority: account(2)?,
+            amount: read_u64(rest)?,
+        },
+        CLOSE_ACCOUNT => EventKind::CloseAccount {
+            account: account(0)?,
+            destination: account(1)?,
+            owner: account(2)?,
+        },
+        FREEZE_ACCOUNT => EventKind::FreezeAccount {
+            account: account(0)?,
+            mint: account(1)?,
+            authority: account(2)?,
+        },
+        THAW_ACCOUNT => EventKind::ThawAccount {
+            account: account(0)?,
+            mint: account(1)?,
+            authority: account(2)?,
+        },
+        TRANSFER_CHECKED => EventKind::TransferChecked {
+            source: account(0)?,
+            mint: account(1)?,
+            destination: account(2)?,
+            authority: account(3)?,
+            amount: read_u64(rest)?,
+            decimals: *rest.get(8)?,
+        },
+        APPROVE_CHECKED => EventKind::ApproveChecked {
+            source: account(0)?,
+            mint: account(1)?,
+            delegate: account(2)?,
+            owner: account(3)?,
+            amount: read_u64(rest)?,
+            decimals: *rest.get(8)?,
+        },
+        MINT_TO_CHECKED => EventKind::MintToChecked {
+            mint: account(0)?,
+            account: account(1)?,
+            authority: account(2)?,
+            amount: read_u64(rest)?,
+            decimals: *rest.get(8)?,
+        },
+        BURN_CHECKED => EventKind::BurnChecked {
+            account: account(0)?,
+            mint: account(1)?,
+            authority: account(2)?,
+            amount: read_u64(rest)?,
+            decimals: *rest.get(8)?,
+        },
+        SYNC_NATIVE => EventKind::SyncNative {
+            account: account(0)?,
+        },
+        _ => return None,
+    };
+
+    Some(event)
+}
+
+pub(crate) fn read_u64(data: &[u8]) -> Option<u64> {
+    let bytes: [u8; 8] = data.get(..8)?.try_into().ok()?;
+
+    Some(u64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accounts(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn data(tag: u8, amount: u64, decimals: Option<u8>) -> Vec<u8> {
+        let mut data = vec![tag];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend(decimals);
+        data
+    }
+
+    #[test]
+    fn test_decode_transfer_with_multisig_signers() {
+        let event = decode(
+            &data(TRANSFER, 1_000_000, None),
+            &accounts(&["source", "destination", "multisig", "signer1", "signer2"]),
+        );
+
+        assert_eq!(
+            event,
+            Some(EventKind::Transfer {
+                source: "source".to_string(),
+                destination: "destination".to_string(),
+                authority: "multisig".to_string(),
+                amount: 1_000_000,
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_transfer_checked() {
+        let event = decode(
+            &data(TRANSFER_CHECKED, 25, Some(6)),
+            &accounts(&["source", "mint", "destination", "owner"]),
+        );
+
+        assert_eq!(
+            event,
+            Some(EventKind::TransferChecked {
+                source: "source".to_string(),
+                mint: "mint".to_string(),
+                destination: "destination".to_string(),
+                authority: "owner".to_string(),
+                amount: 25,
+                decimals: 6,
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_three_account_instructions_are_not_transfers() {
+        let three = accounts(&["a", "b", "c"]);
+
+        let approve = decode(&data(APPROVE, 5, None), &three).unwrap();
+        let mint_to = decode(&data(MINT_TO, 5, None), &three).unwrap();
+        let burn = decode(&data(BURN, 5, None), &three).unwrap();
+
+        assert!(matches!(approve, EventKind::Approve { amount: 5, .. }));
+        assert!(matches!(mint_to, EventKind::MintTo { amount: 5, .. }));
+        assert!(matches!(burn, EventKind::Burn { amount: 5, .. }));
+        assert!(approve.transfer().is_none());
+        assert!(mint_to.transfer().is_none());
+        assert!(burn.transfer().is_none());
+    }
+
+    #[test]
+    fn test_decode_malformed_instruction() {
+        assert_eq!(decode(&[], &accounts(&["a", "b", "c"])), None);
+        assert_eq!(decode(&[TRANSFER, 1, 2], &accounts(&["a", "b", "c"])), None);
+        assert_eq!(
+            decode(&data(TRANSFER, 1, None), &accounts(&["a", "b"])),
+            None
+        );
+        assert_eq!(decode(&[1], &accounts(&["a", "b", "c", "d"])), None);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::domain::event::EventKind;
+use crate::infrastructure::decoders::spl_token::{self, read_u64};
+
+const TRANSFER_FEE_EXTENSION: u8 = 26;
+const CONFIDENTIAL_TRANSFER_EXTENSION: u8 = 27;
+
+const TRANSFER_CHECKED_WITH_FEE: u8 = 1;
+const WITHDRAW_WITHHELD_TOKENS_FROM_MINT: u8 = 2;
+const WITHDRAW_WITHHELD_TOKENS_FROM_ACCOUNTS: u8 = 3;
+const HARVEST_WITHHELD_TOKENS_TO_MINT: u8 = 4;
+
+const CONFIDENTIAL_DEPOSIT: u8 = 5;
+const CONFIDENTIAL_WITHDRAW: u8 = 6;
+const CONFIDENTIAL_TRANSFER: u8 = 7;
+const CONFIDENTIAL_TRANSFER_WITH_SPLIT_PROOFS: u8 = 13;
+
+// Token-2022 keeps the SPL Token instruction layout and adds extensions on top.
+// Transfers on mints with a transfer hook are regular `TransferChecked`
+// instructions whose extra hook accounts trail the authority, so the base
+// decoder already handles them.
+pub fn decode(data: &[u8], accounts: &[String]) -> Option<EventKind> {
+    let (tag, rest) = data.split_first()?;
+
+    match *tag {
+        TRANSFER_FEE_EXTENSION => decode_transfer_fee(rest, accounts),
+        CONFIDENTIAL_TRANSFER_EXTENSION => decode_confidential_transfer(rest, accounts),
+        _ => spl_token::decode(data, accounts),
+    }
+}
+
+fn decode_transfer_fee(data: &[u8], accounts: &[String]) -> Option<EventKind> {
+    let (tag, rest) = data.split_first()?;
+    let account = |position: usize| accounts.get(position).cloned();
+
+    let event = match *tag {
+        TRANSFER_CHECKED_WITH_FEE => EventKind::TransferCheckedWithFee {
+            source: account(0)?,
+            mint: account(1)?,
+            destination: account(2)?,
+            authority: account(3)?,
+            amount: read_u64(rest)?,
+            decimals: *rest.get(8)?,
+            fee: read_u64(rest.get(9..)?)?,
+        },
+        WITHDRAW_WITHHELD_TOKENS_FROM_MINT => EventKind::WithdrawWithheldTokensFromMint {
+            mint: account(0)?,
+            destination: account(1)?,
+            authority: account(2)?,
+        },
+        WITHDRAW_WITHHELD_TOKENS_FROM_ACCOUNTS => {
+            // The sources come last, after any multisig signers.
+            let count = *rest.first()? as usize;
+            let sources = accounts.get(accounts.len().checked_sub(count)?..)?;
+
+            EventKind::WithdrawWithheldTokensFromAccounts {
+                mint: account(0)?,
+                destination: account(1)?,
+                authority: account(2)?,
+                sources: sources.to_vec(),
+            }
+        }
+        HARVEST_WITHHELD_TOKENS_TO_MINT => EventKind::HarvestWithheldTokensToMint {
+            mint: account(0)?,
+            sources: accounts.get(1..)?.to_vec(),
+        },
+        _ => return None,
+    };
+
+    Some(event)
+}
+
+fn decode_confidential_transfer(data: &[u8], accounts: &[String]) -> Option<EventKind> {
+    let (tag, rest) = data.split_first()?;
+    let account = |position: usize| accounts.get(position).cloned();
+
+    let event = match *tag {
+        CONFIDENTIAL_DEPOSIT => EventKind::ConfidentialDeposit {
+            account: account(0)?,
+            mint: account(1)?,
+            amount: read_u64(rest)?,
+            decimals: *rest.get(8)?,
+        },
+        CONFIDENTIAL_WITHDRAW => EventKind::ConfidentialWithdraw {
+            account: account(0)?,
+            mint: account(1)?,
+            amount: read_u64(rest)?,
+            decimals: *rest.get(8)?,
+        },
+        CONFIDENTIAL_TRANSFER | CONFIDENTIAL_TRANSFER_WITH_SPLIT_PROOFS => {
+            EventKind::ConfidentialTransfer {
+                source: account(0)?,
+                mint: account(1)?,
+                destination: account(2)?,
+            }
+        }
+        _ => return None,
+    };
+
+    Some(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accounts(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_decode_transfer_checked_with_fee() {
+        let mut data = vec![TRANSFER_FEE_EXTENSION, TRANSFER_CHECKED_WITH_FEE];
+        data.extend_from_slice(&1_000_000u64.to_le_bytes());
+        data.push(6);
+        data.extend_from_slice(&5_000u64.to_le_bytes());
+
+        let event = decode(
+            &data,
+            &accounts(&["source", "mint", "destination", "owner"]),
+        )
+        .unwrap();
+
+        assert_eq!(event.transfer(), Some(("source", "destination")));
+        assert_eq!(event.amount(), Some(1_000_000));
+        assert_eq!(event.fee(), Some(5_000));
+    }
+
+    #[test]
+    fn test_decode_withdraw_withheld_tokens_from_accounts() {
+        let event = decode(
+            &[
+                TRANSFER_FEE_EXTENSION,
+                WITHDRAW_WITHHELD_TOKENS_FROM_ACCOUNTS,
+                2,
+            ],
+            &accounts(&["mint", "destination", "multisig", "signer", "a", "b"]),
+        );
+
+        assert_eq!(
+            event,
+            Some(EventKind::WithdrawWithheldTokensFromAccounts {
+                mint: "mint".to_string(),
+                destination: "destination".to_string(),
+                authority: "multisig".to_string(),
+                sources: accounts(&["a", "b"]),
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_confidential_transfer_has_no_amount() {
+        let event = decode(
+            &[CONFIDENTIAL_TRANSFER_EXTENSION, CONFIDENTIAL_TRANSFER, 0, 0],
+            &accounts(&["source", "mint", "destination", "sysvar", "owner"]),
+        )
+        .unwrap();
+
+        assert_eq!(event.transfer(), Some(("source", "destination")));
+        assert_eq!(event.amount(), None);
+    }
+
+    #[test]
+    fn test_decode_falls_back_to_spl_token() {
+        let mut data = vec![12];
+        data.extend_from_slice(&10u64.to_le_bytes());
+        data.push(6);
+
+        let event = decode(
+            &data,
+            &accounts(&["source", "mint", "destination", "owner", "hook", "extra"]),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            event,
+            EventKind::TransferChecked { amount: 10, .. }
+        ));
+    }
+}
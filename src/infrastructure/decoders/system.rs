@@ -0,0 +1,105 @@
+use crate::domain::event::EventKind;
+use crate::infrastructure::decoders::spl_token::read_u64;
+
+const CREATE_ACCOUNT: u32 = 0;
+const TRANSFER: u32 = 2;
+const CREATE_ACCOUNT_WITH_SEED: u32 = 3;
+const WITHDRAW_NONCE_ACCOUNT: u32 = 5;
+const TRANSFER_WITH_SEED: u32 = 11;
+
+// Decodes the System program instructions that move lamports. The System
+// program uses a little-endian `u32` discriminant instead of a single byte.
+pub fn decode(data: &[u8], accounts: &[String]) -> Option<EventKind> {
+    let tag = u32::from_le_bytes(data.get(..4)?.try_into().ok()?);
+    let rest = &data[4..];
+    let account = |position: usize| accounts.get(position).cloned();
+
+    let event = match tag {
+        CREATE_ACCOUNT => EventKind::CreateAccount {
+            source: account(0)?,
+            account: account(1)?,
+            lamports: read_u64(rest)?,
+        },
+        TRANSFER => EventKind::SystemTransfer {
+            source: account(0)?,
+            destination: account(1)?,
+            lamports: read_u64(rest)?,
+        },
+        CREATE_ACCOUNT_WITH_SEED => {
+            // base: Pubkey, seed: String (u64 length prefix), lamports: u64
+            let seed_length = read_u64(rest.get(32..)?)? as usize;
+            let lamports_offset = 40usize.checked_add(seed_length)?;
+
+            EventKind::CreateAccount {
+                source: account(0)?,
+                account: account(1)?,
+                lamports: read_u64(rest.get(lamports_offset..)?)?,
+            }
+        }
+        WITHDRAW_NONCE_ACCOUNT => EventKind::SystemTransfer {
+            source: account(0)?,
+            destination: account(1)?,
+            lamports: read_u64(rest)?,
+        },
+        TRANSFER_WITH_SEED => EventKind::SystemTransferWithSeed {
+            source: account(0)?,
+            base: account(1)?,
+            destination: account(2)?,
+            lamports: read_u64(rest)?,
+        },
+        _ => return None,
+    };
+
+    Some(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accounts(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_decode_transfer() {
+        let mut data = TRANSFER.to_le_bytes().to_vec();
+        data.extend_from_slice(&1_000_000_000u64.to_le_bytes());
+
+        let event = decode(&data, &accounts(&["from", "to"])).unwrap();
+
+        assert_eq!(event.transfer(), Some(("from", "to")));
+        assert_eq!(event.amount(), Some(1_000_000_000));
+    }
+
+    #[test]
+    fn test_decode_create_account_with_seed() {
+        let seed = b"stake:0";
+        let mut data = CREATE_ACCOUNT_WITH_SEED.to_le_bytes().to_vec();
+        data.extend_from_slice(&[7; 32]);
+        data.extend_from_slice(&(seed.len() as u64).to_le_bytes());
+        data.extend_from_slice(seed);
+        data.extend_from_slice(&2_282_880u64.to_le_bytes());
+        data.extend_from_slice(&200u64.to_le_bytes());
+        data.extend_from_slice(&[9; 32]);
+
+        let event = decode(&data, &accounts(&["funder", "new", "base"])).unwrap();
+
+        assert_eq!(
+            event,
+            EventKind::CreateAccount {
+                source: "funder".to_string(),
+                account: "new".to_string(),
+                lamports: 2_282_880,
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_ignores_other_instructions() {
+        let assign = 1u32.to_le_bytes();
+
+        assert_eq!(decode(&assign, &accounts(&["account"])), None);
+        assert_eq!(decode(&[2, 0], &accounts(&["from", "to"])), None);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::domain::event::EventKind;
+use crate::domain::mint::NATIVE_MINT;
+use std::collections::HashSet;
+
+// Wrapped SOL accounts among the events of a transaction, starting from those
+// known from the token balances. An account created and closed in the same
+// transaction has no token balance, it is recognised by being synced or by
+// moving tokens of the native mint or to or from another wrapped SOL account.
+pub fn wrapped_accounts(events: &[EventKind], known: &HashSet<String>) -> HashSet<String> {
+    let mut wrapped = known.clone();
+
+    loop {
+        let count = wrapped.len();
+
+        for event in events {
+            match event {
+                EventKind::SyncNative { account } => {
+                    wrapped.insert(account.clone());
+                }
+                EventKind::TransferChecked {
+                    source,
+                    mint,
+                    destination,
+                    ..
+                } if mint == NATIVE_MINT => {
+                    wrapped.insert(source.clone());
+                    wrapped.insert(destination.clone());
+                }
+                EventKind::Transfer {
+                    source,
+                    destination,
+                    ..
+                } if wrapped.contains(source) || wrapped.contains(destination) => {
+                    wrapped.insert(source.clone());
+                    wrapped.insert(destination.clone());
+                }
+                _ => {}
+            }
+        }
+
+        if wrapped.len() == count {
+            return wrapped;
+        }
+    }
+}
+
+// The lamports a wrapped SOL account held when it was closed: what it held
+// before the transaction and received during it, less what it sent on and
+// what it still holds after.
+pub fn closed_lamports(
+    events: &[EventKind],
+    account: &str,
+    pre_lamports: u64,
+    post_lamports: u64,
+) -> u64 {
+    let mut received: u64 = 0;
+    let mut sent: u64 = 0;
+
+    for event in events {
+        if let (Some((source, destination)), Some(amount)) = (event.transfer(), event.amount()) {
+            if destination == account {
+                received = received.saturating_add(amount);
+            }
+            if source == account {
+                sent = sent.saturating_add(amount);
+            }
+        }
+    }
+
+    pre_lamports
+        .saturating_add(received)
+        .saturating_sub(sent)
+        .saturating_sub(post_lamports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RENT: u64 = 2_039_280;
+
+    fn transfer(source: &str, destination: &str, amount: u64) -> EventKind {
+        EventKind::Transfer {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            authority: "owner".to_string(),
+            amount,
+        }
+    }
+
+    fn close(account: &str) -> EventKind {
+        EventKind::CloseAccount {
+            account: account.to_string(),
+            destination: "owner".to_string(),
+            owner: "owner".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_created_and_closed() {
+        // Wrap 1 SOL, swap it away and unwrap the rent that is left.
+        let events = vec![
+            EventKind::CreateAccount {
+                source: "owner".to_string(),
+                account: "wsol".to_string(),
+                lamports: RENT,
+            },
+            EventKind::SystemTransfer {
+                source: "owner".to_string(),
+                destination: "wsol".to_string(),
+                lamports: 1_000_000_000,
+            },
+            EventKind::SyncNative {
+                account: "wsol".to_string(),
+            },
+            transfer("wsol", "pool", 1_000_000_000),
+            close("wsol"),
+        ];
+
+        let wrapped = wrapped_accounts(&events, &HashSet::new());
+        assert!(wrapped.contains("wsol"));
+        assert!(wrapped.contains("pool"));
+        assert_eq!(closed_lamports(&events, "wsol", 0, 0), RENT);
+    }
+
+    #[test]
+    fn test_funded_then_closed() {
+        // Swap into a wrapped SOL account that already held 0.5 SOL and unwrap it all.
+        let events = vec![transfer("pool", "wsol", 2_000_000_000), close("wsol")];
+        let known = HashSet::from(["pool".to_string()]);
+
+        assert!(wrapped_accounts(&events, &known).contains("wsol"));
+        assert_eq!(
+            closed_lamports(&events, "wsol", RENT + 500_000_000, 0),
+            RENT + 2_500_000_000
+        );
+    }
+
+    #[test]
+    fn test_other_accounts_are_not_wrapped() {
+        let events = vec![transfer("usdc", "other", 5), close("usdc")];
+
+        assert!(wrapped_accounts(&events, &HashSet::new()).is_empty());
+    }
+}
@@ -0,0 +1,288 @@
+use crate::application::gateways::alert_sink::AlertSink;
+use crate::application::presenters::SCHEMA_VERSION;
+use crate::domain::alert::Alert;
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+const EXEC_TIMEOUT: Duration = Duration::from_secs(10);
+const EXEC_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// What the file, exec and webhook sinks write. `text` is the message on its
+// own, which chat webhooks such as Slack's display as is.
+#[derive(Serialize)]
+struct AlertRecord<'a> {
+    schema_version: u32,
+    text: &'a str,
+    alert: &'a Alert,
+}
+
+fn record(alert: &Alert) -> Result<String> {
+    Ok(serde_json::to_string(&AlertRecord {
+        schema_version: SCHEMA_VERSION,
+        text: &alert.message,
+        alert,
+    })?)
+}
+
+fn describe(alert: &Alert) -> String {
+    match alert.suppressed {
+        0 => alert.message.clone(),
+        suppressed => format!(
+            "{} (+{} alerts suppressed by the rate limit)",
+            alert.message, suppressed
+        ),
+    }
+}
+
+pub struct StdoutSink;
+
+impl AlertSink for StdoutSink {
+    fn send(&mut self, alert: &Alert) -> Result<()> {
+        println!("ALERT [slot {}] {}", alert.slot, describe(alert));
+
+        Ok(())
+    }
+}
+
+// The default, out of the way of the blocks written to stdout.
+pub struct StderrSink;
+
+impl AlertSink for StderrSink {
+    fn send(&mut self, alert: &Alert) -> Result<()> {
+        eprintln!("ALERT [slot {}] {}", alert.slot, describe(alert));
+
+        Ok(())
+    }
+}
+
+// Appends one JSON line per alert.
+pub struct FileSink {
+    file: File,
+}
+
+impl FileSink {
+    pub fn new(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("opening alert file {}", path.display()))?;
+
+        Ok(Self { file })
+    }
+}
+
+impl AlertSink for FileSink {
+    fn send(&mut self, alert: &Alert) -> Result<()> {
+        writeln!(self.file, "{}", record(alert)?)?;
+
+        Ok(self.file.flush()?)
+    }
+}
+
+// Runs a command per alert with the JSON record on stdin and the essentials
+// in `ALERT_RULE`, `ALERT_MESSAGE`, `ALERT_SLOT` and `ALERT_SIGNATURE`. A
+// command still running after the timeout is killed, so a hung one does not
+// hold up the alerts after it.
+pub struct ExecSink {
+    command: Vec<String>,
+    timeout: Duration,
+}
+
+impl ExecSink {
+    pub fn new(command: Vec<String>) -> Result<Self> {
+        if command.is_empty() {
+            return Err(anyhow!("the exec alert sink needs a command"));
+        }
+
+        Ok(Self {
+            command,
+            timeout: EXEC_TIMEOUT,
+        })
+    }
+}
+
+impl AlertSink for ExecSink {
+    fn send(&mut self, alert: &Alert) -> Result<()> {
+        let mut child = Command::new(&self.command[0])
+            .args(&self.command[1..])
+            .env("ALERT_RULE", &alert.rule)
+            .env("ALERT_MESSAGE", describe(alert))
+            .env("ALERT_SLOT", alert.slot.to_string())
+            .env("ALERT_SIGNATURE", &alert.transaction.signature)
+            .stdin(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("running {:?}", self.command[0]))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // The command may not care about stdin and exit early.
+            let _ = writeln!(stdin, "{}", record(alert)?);
+        }
+
+        let started = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if started.elapsed() >= self.timeout {
+                child.kill()?;
+                child.wait()?;
+                return Err(anyhow!(
+                    "{:?} did not exit within {}s",
+                    self.command[0],
+                    self.timeout.as_secs()
+                ));
+            }
+            std::thread::sleep(EXEC_POLL_INTERVAL);
+        };
+        if !status.success() {
+            return Err(anyhow!("{:?} exited with {}", self.command[0], status));
+        }
+
+        Ok(())
+    }
+}
+
+// POSTs the JSON record to a URL.
+pub struct WebhookSink {
+    url: String,
+    // Created on first use, on the sink thread, as the blocking client must
+    // not live inside the async runtime.
+    client: Option<reqwest::blocking::Client>,
+}
+
+impl WebhookSink {
+    pub fn new<U: ToString>(url: U) -> Self {
+        Self {
+            url: url.to_string(),
+            client: None,
+        }
+    }
+}
+
+impl AlertSink for WebhookSink {
+    fn send(&mut self, alert: &Alert) -> Result<()> {
+        let client = match self.client.take() {
+            Some(client) => client,
+            None => reqwest::blocking::Client::builder()
+                .timeout(WEBHOOK_TIMEOUT)
+                .build()?,
+        };
+        let response = client
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .body(record(alert)?)
+            .send();
+        self.client = Some(client);
+
+        let status = response?.status();
+        if !status.is_success() {
+            return Err(anyhow!("webhook {} answered {}", self.url, status));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::amount::Amount;
+    use crate::domain::mint::Mint;
+    use crate::domain::program::Program;
+    use crate::domain::transaction::Transaction;
+    use std::io::{BufRead, BufReader, Read};
+    use std::net::TcpListener;
+
+    fn alert() -> Alert {
+        Alert {
+            rule: "whale".to_string(),
+            slot: 42,
+            message: "whale: alice sent 5000 USDC to bob".to_string(),
+            transaction: Transaction::new(
+                "sig".to_string(),
+                Account::new("alice", 1, Amount::zero(6)),
+                Account::new("bob", 2, Amount::zero(6)),
+                Program::new("program", 3),
+                "USDC".parse::<Mint>().unwrap(),
+                Amount::new(5_000_000_000, 6),
+            ),
+            suppressed: 0,
+        }
+    }
+
+    #[test]
+    fn test_webhook_sink() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+
+        // A stand-in server that answers a single request and hands back its body.
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            let mut length = 0;
+            reader.read_line(&mut request_line).unwrap();
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+
+            (request_line, String::from_utf8(body).unwrap())
+        });
+
+        WebhookSink::new(url).send(&alert()).unwrap();
+
+        let (request_line, body) = server.join().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert!(request_line.starts_with("POST /hook "));
+        assert_eq!(value["text"], "whale: alice sent 5000 USDC to bob");
+        assert_eq!(value["alert"]["transaction"]["amount"]["ui"], "5000");
+    }
+
+    #[test]
+    fn test_exec_sink_timeout() {
+        let mut sink = ExecSink::new(vec!["sleep".to_string(), "30".to_string()]).unwrap();
+        sink.timeout = Duration::from_millis(200);
+
+        let started = Instant::now();
+        let error = sink.send(&alert()).unwrap_err();
+        assert!(error.to_string().contains("did not exit within"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_file_sink() {
+        let path = std::env::temp_dir().join(format!("alerts-{}.jsonl", std::process::id()));
+        let mut sink = FileSink::new(&path).unwrap();
+
+        sink.send(&alert()).unwrap();
+        sink.send(&alert()).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(content.lines().count(), 2);
+        assert!(content.starts_with("{\"schema_version\":1,"));
+    }
+}
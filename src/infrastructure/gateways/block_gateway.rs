@@ -1,48 +1,132 @@
-use crate::application::gateways::block_gateway::BlockGateway;
+use crate::application::gateways::block_gateway::{BlockGateway, BlockUpdate, Unsubscribe};
 use crate::domain::account::Account;
+use crate::domain::amount::Amount;
 use crate::domain::block::Block;
-use crate::domain::program::Program;
+use crate::domain::commitment::{Commitment, Finality, FinalityTracker};
+use crate::domain::connection::ConnectionState;
+use crate::domain::event::{Event, EventKind};
+use crate::domain::mint::{Mint, NATIVE_DECIMALS, NATIVE_MINT};
+use crate::domain::program::{
+    Program, ProgramKind, ASSOCIATED_TOKEN_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_2022_PROGRAM_ID,
+    TOKEN_PROGRAM_ID,
+};
+use crate::domain::slot_tracker::{Backoff, SlotOutcome, SlotTracker};
 use crate::domain::transaction::Transaction;
-use anyhow::Result;
-use futures_util::{Future, StreamExt};
+use crate::domain::transaction_detail::{Instruction, TransactionDetail};
+use crate::infrastructure::decoders::{spl_token, spl_token_2022, system, wrapped_sol};
+use anyhow::{anyhow, Result};
+use futures_util::stream::{self, BoxStream};
+use futures_util::StreamExt;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::{RpcBlockConfig, RpcTransactionConfig};
+use solana_client::rpc_custom_error::{
+    JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED, JSON_RPC_SERVER_ERROR_SLOT_SKIPPED,
+};
+use solana_client::rpc_request::RpcError;
 use solana_client::rpc_response::SlotUpdate;
-use solana_client::{rpc_client::RpcClient, rpc_config::RpcBlockConfig};
 use solana_pubsub_client::nonblocking::pubsub_client::PubsubClient;
+use solana_sdk::bs58;
 use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
 use solana_transaction_status::option_serializer::OptionSerializer;
 use solana_transaction_status::{
-    EncodedTransaction, TransactionDetails, UiInstruction, UiMessage, UiTransaction,
-    UiTransactionEncoding, UiTransactionStatusMeta,
+    EncodedTransaction, TransactionDetails, UiCompiledInstruction, UiInstruction, UiMessage,
+    UiTransaction, UiTransactionEncoding, UiTransactionStatusMeta,
 };
-use std::collections::HashMap;
-use std::pin::Pin;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::oneshot;
+
+const MAX_GET_BLOCKS_RANGE: u64 = 500_000;
+
+// Blocks of just completed slots are often not available yet, failed slots
+// are retried for about two minutes.
+const RETRY_BACKOFF: Backoff = Backoff {
+    initial: Duration::from_secs(1),
+    max: Duration::from_secs(30),
+    attempts: 8,
+};
+
+// A connection without any slot update for this long is considered stalled.
+pub const DEFAULT_STALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Slots to catch up on are fetched this many at a time, in passes of at most
+// this many slots so updates and unsubscribing are attended to in between.
+const CATCH_UP_CONCURRENCY: usize = 8;
+const CATCH_UP_BATCH: usize = 64;
+
+// Blocks sent below finalized are checked against the finalized ones this
+// often, a few times per finalization delay.
+const FINALITY_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+// Reconnection attempts wait longer each time, and never give up.
+const RECONNECT_BACKOFF: Backoff = Backoff {
+    initial: Duration::from_secs(1),
+    max: Duration::from_secs(60),
+    attempts: u32::MAX,
+};
 
-const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+fn is_skipped(error: &ClientError) -> bool {
+    matches!(
+        error.kind(),
+        ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            code: JSON_RPC_SERVER_ERROR_SLOT_SKIPPED
+                | JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED,
+            ..
+        })
+    )
+}
 
 #[derive(Clone)]
 pub struct BlockGatewayImpl {
     rpc_url: String,
     websocket_url: String,
+    mints: Vec<Mint>,
+    stall_timeout: Duration,
+    commitment: Commitment,
 }
 
 impl BlockGatewayImpl {
-    pub fn new<U: ToString>(cluster: U) -> Self {
+    pub fn new<U: ToString>(rpc_url: U, websocket_url: U, mints: Vec<Mint>) -> Self {
         Self {
-            rpc_url: format!("https://api.{}.solana.com", cluster.to_string()),
-            websocket_url: format!("wss://api.{}.solana.com/", cluster.to_string()),
+            rpc_url: rpc_url.to_string(),
+            websocket_url: websocket_url.to_string(),
+            mints,
+            stall_timeout: DEFAULT_STALL_TIMEOUT,
+            commitment: Commitment::Finalized,
         }
     }
 
-    fn get_accounts(&self, meta: &UiTransactionStatusMeta) -> HashMap<u8, Account> {
+    pub fn update_stall_timeout(&mut self, stall_timeout: Duration) {
+        self.stall_timeout = stall_timeout;
+    }
+
+    pub fn update_commitment(&mut self, commitment: Commitment) {
+        self.commitment = commitment;
+    }
+
+    // getBlock serves nothing below confirmed, so that is the earliest a
+    // processed block can be fetched.
+    fn block_commitment(&self) -> Commitment {
+        self.commitment.max(Commitment::Confirmed)
+    }
+
+    #[allow(clippy::needless_return)]
+    fn get_accounts(&self, meta: &UiTransactionStatusMeta, mint: &Mint) -> HashMap<u8, Account> {
         let mut accounts: HashMap<u8, Account> = HashMap::new();
 
         if let OptionSerializer::Some(pre_token_balances) = &meta.pre_token_balances {
             for balance in pre_token_balances {
-                if balance.mint == USDC_MINT {
+                if balance.mint == mint.address {
                     if let OptionSerializer::Some(owner) = &balance.owner {
-                        if let Some(pre_balance) = balance.ui_token_amount.ui_amount {
+                        let amount = &balance.ui_token_amount;
+
+                        if let Ok(pre_balance) = Amount::parse(&amount.amount, amount.decimals) {
                             let account = Account::new(owner, balance.account_index, pre_balance);
                             accounts.insert(account.index, account);
                         }
@@ -52,11 +136,28 @@ impl BlockGatewayImpl {
         }
 
         if let OptionSerializer::Some(post_token_balances) = &meta.post_token_balances {
+            // Accounts closed by the transaction have no post balance left.
             for account in accounts.values_mut() {
-                for balance in post_token_balances {
-                    if account.index == balance.account_index {
-                        if let Some(post_balance) = balance.ui_token_amount.ui_amount {
-                            account.update_post_balance(post_balance);
+                account.update_post_balance(Amount::zero(account.pre_balance.decimals));
+            }
+
+            for balance in post_token_balances {
+                if balance.mint == mint.address {
+                    if let OptionSerializer::Some(owner) = &balance.owner {
+                        let amount = &balance.ui_token_amount;
+
+                        if let Ok(post_balance) = Amount::parse(&amount.amount, amount.decimals) {
+                            // Accounts created by the transaction start from zero.
+                            accounts
+                                .entry(balance.account_index)
+                                .or_insert_with(|| {
+                                    Account::new(
+                                        owner,
+                                        balance.account_index,
+                                        Amount::zero(amount.decimals),
+                                    )
+                                })
+                                .update_post_balance(post_balance);
                         }
                     }
                 }
@@ -66,11 +167,34 @@ impl BlockGatewayImpl {
         return accounts;
     }
 
-    fn get_program(
+    fn get_lamport_accounts(
+        &self,
+        meta: &UiTransactionStatusMeta,
+        addresses: &[String],
+    ) -> HashMap<u8, Account> {
+        addresses
+            .iter()
+            .enumerate()
+            .filter_map(|(index, address)| {
+                let pre_balance = *meta.pre_balances.get(index)?;
+                let post_balance = *meta.post_balances.get(index)?;
+                let mut account = Account::new(
+                    address,
+                    index as u8,
+                    Amount::new(pre_balance, NATIVE_DECIMALS),
+                );
+                account.update_post_balance(Amount::new(post_balance, NATIVE_DECIMALS));
+
+                Some((account.index, account))
+            })
+            .collect()
+    }
+
+    fn get_addresses(
         &self,
         meta: &UiTransactionStatusMeta,
         transaction: &UiTransaction,
-    ) -> Option<Program> {
+    ) -> Vec<String> {
         let mut addresses: Vec<String> = Vec::new();
 
         if let UiMessage::Raw(message) = &transaction.message {
@@ -84,9 +208,25 @@ impl BlockGatewayImpl {
             addresses.append(&mut load_addresses_clone.readonly.clone());
         }
 
-        if let OptionSerializer::Some(pre_token_balances) = &meta.pre_token_balances {
-            for balance in pre_token_balances {
-                if balance.mint == USDC_MINT {
+        addresses
+    }
+
+    #[allow(clippy::needless_return)]
+    fn get_program(
+        &self,
+        meta: &UiTransactionStatusMeta,
+        addresses: &[String],
+        mint: &Mint,
+    ) -> Option<Program> {
+        let balances = [&meta.pre_token_balances, &meta.post_token_balances];
+
+        for token_balances in balances {
+            if let OptionSerializer::Some(token_balances) = token_balances {
+                for balance in token_balances {
+                    if balance.mint != mint.address {
+                        continue;
+                    }
+
                     if let OptionSerializer::Some(program_id) = &balance.program_id {
                         let mut account_keys = addresses.iter();
                         let index = account_keys
@@ -102,165 +242,907 @@ impl BlockGatewayImpl {
         return None;
     }
 
-    fn get_account_pairs(
+    fn get_instructions<'a>(
         &self,
-        program: &Program,
-        meta: &UiTransactionStatusMeta,
-        transaction: &UiTransaction,
-    ) -> Vec<(u8, u8)> {
-        let mut account_pairs: Vec<(u8, u8)> = Vec::new();
+        meta: &'a UiTransactionStatusMeta,
+        transaction: &'a UiTransaction,
+    ) -> Vec<&'a UiCompiledInstruction> {
+        let mut instructions: Vec<&UiCompiledInstruction> = Vec::new();
 
         if let UiMessage::Raw(message) = &transaction.message {
-            let _ = &message.instructions.iter().for_each(|instruction| {
-                if instruction.program_id_index == program.index && instruction.accounts.len() == 3
-                {
-                    let source = instruction.accounts.get(0).unwrap();
-                    let destination = instruction.accounts.get(1).unwrap();
-
-                    account_pairs.push((source.to_owned(), destination.to_owned()));
-                }
-            });
+            instructions.extend(message.instructions.iter());
         }
 
         if let OptionSerializer::Some(inner_instructions) = &meta.inner_instructions {
             for inner_instruction in inner_instructions {
                 for instruction in &inner_instruction.instructions {
                     if let UiInstruction::Compiled(compiled) = &instruction {
-                        if compiled.program_id_index == program.index
-                            && compiled.accounts.len() == 3
-                        {
-                            let source = compiled.accounts.get(0).unwrap();
-                            let destination = compiled.accounts.get(1).unwrap();
+                        instructions.push(compiled);
+                    }
+                }
+            }
+        }
+
+        instructions
+    }
+
+    fn decode_instruction(
+        &self,
+        kind: ProgramKind,
+        instruction: &UiCompiledInstruction,
+        addresses: &[String],
+    ) -> Option<EventKind> {
+        let data = bs58::decode(&instruction.data).into_vec().ok()?;
+        let accounts = instruction
+            .accounts
+            .iter()
+            .map(|index| addresses.get(*index as usize).cloned())
+            .collect::<Option<Vec<String>>>()?;
+
+        match kind {
+            ProgramKind::System => system::decode(&data, &accounts),
+            ProgramKind::Token => spl_token::decode(&data, &accounts),
+            ProgramKind::Token2022 => spl_token_2022::decode(&data, &accounts),
+            ProgramKind::Other => None,
+        }
+    }
+
+    // The decoded events of the program, with the index of their instruction.
+    fn get_events(
+        &self,
+        program: &Program,
+        meta: &UiTransactionStatusMeta,
+        transaction: &UiTransaction,
+        addresses: &[String],
+    ) -> Vec<(usize, EventKind)> {
+        self.get_instructions(meta, transaction)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, instruction)| instruction.program_id_index == program.index)
+            .filter_map(|(index, instruction)| {
+                self.decode_instruction(program.kind, instruction, addresses)
+                    .map(|kind| (index, kind))
+            })
+            .collect()
+    }
+
+    fn get_instruction_tree(
+        &self,
+        meta: &UiTransactionStatusMeta,
+        transaction: &UiTransaction,
+        addresses: &[String],
+    ) -> Vec<Instruction> {
+        let to_instruction = |compiled: &UiCompiledInstruction, stack_height: u32| {
+            let program = addresses
+                .get(compiled.program_id_index as usize)
+                .cloned()
+                .unwrap_or_default();
+            let accounts = compiled
+                .accounts
+                .iter()
+                .filter_map(|index| addresses.get(*index as usize).cloned())
+                .collect();
+            let event =
+                self.decode_instruction(ProgramKind::from_address(&program), compiled, addresses);
+
+            Instruction::new(
+                program,
+                accounts,
+                compiled.data.clone(),
+                compiled.stack_height.unwrap_or(stack_height),
+                event,
+            )
+        };
+
+        let mut instructions: Vec<Instruction> = Vec::new();
 
-                            account_pairs.push((source.to_owned(), destination.to_owned()));
+        if let UiMessage::Raw(message) = &transaction.message {
+            for compiled in &message.instructions {
+                instructions.push(to_instruction(compiled, 1));
+            }
+        }
+
+        if let OptionSerializer::Some(inner_instructions) = &meta.inner_instructions {
+            for inner_instruction in inner_instructions {
+                if let Some(parent) = instructions.get_mut(inner_instruction.index as usize) {
+                    for instruction in &inner_instruction.instructions {
+                        if let UiInstruction::Compiled(compiled) = &instruction {
+                            parent.add_inner_instruction(to_instruction(compiled, 2));
                         }
                     }
                 }
             }
         }
 
-        return account_pairs;
+        instructions
     }
-}
 
-impl BlockGateway for BlockGatewayImpl {
-    async fn subscribe(
-        self: Arc<Self>,
-        ready_sender: &UnboundedSender<()>,
-        unsubscribe_sender: &UnboundedSender<
-            Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>,
-        >,
-        block_update_sender: &UnboundedSender<Block>,
-    ) -> Result<()> {
-        let pubsub_client = Arc::new(PubsubClient::new(&self.websocket_url.as_str()).await?);
+    // Fetches a batch of the slots that are due and sends the blocks of the
+    // settled ones, in slot order.
+    async fn fetch_due(
+        self: &Arc<Self>,
+        tracker: &mut SlotTracker,
+        finality: &mut FinalityTracker,
+        block_update_sender: &UnboundedSender<BlockUpdate>,
+    ) {
+        // Slots fetched again are after the block finalized in them.
+        let slots: Vec<(u64, Commitment)> = tracker
+            .due(Instant::now(), CATCH_UP_BATCH)
+            .into_iter()
+            .map(|slot| {
+                if tracker.is_refetch(slot) {
+                    (slot, Commitment::Finalized)
+                } else {
+                    (slot, self.block_commitment())
+                }
+            })
+            .collect();
+        let mut blocks = stream::iter(slots)
+            .map(|(slot, commitment)| {
+                let block_gateway = Arc::clone(self);
 
-        tokio::spawn({
-            let ready_sender = ready_sender.clone();
-            let unsubscribe_sender = unsubscribe_sender.clone();
-            let block_update_sender = block_update_sender.clone();
-            let pubsub_client = Arc::clone(&pubsub_client);
+                async move {
+                    let block = tokio::task::spawn_blocking(move || {
+                        block_gateway.fetch_block(slot, commitment)
+                    })
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|block| block);
+                    (slot, block)
+                }
+            })
+            .buffered(CATCH_UP_CONCURRENCY);
 
-            async move {
-                let (mut slot_updates_notifications, slot_updates_unsubscribe) =
-                    pubsub_client.slot_updates_subscribe().await?;
-
-                // With the subscription started,
-                // send a signal back to the main task for synchronization.
-                ready_sender.send(()).expect("channel");
-
-                // Send the unsubscribe closure back to the main task.
-                unsubscribe_sender
-                    .send(slot_updates_unsubscribe)
-                    .map_err(|e| format!("{}", e))
-                    .expect("channel");
-
-                drop((ready_sender, unsubscribe_sender));
-
-                while let Some(slot_info) = slot_updates_notifications.next().await {
-                    if let SlotUpdate::Completed { slot, timestamp: _ } = slot_info {
-                        if let Ok(block) = self.get_block(slot) {
-                            block_update_sender.send(block).expect("channel");
-                        } else {
-                            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        while let Some((slot, block)) = blocks.next().await {
+            match block {
+                Ok(Some(block)) => tracker.fetched(block),
+                Ok(None) => tracker.skipped(slot),
+                Err(e) => tracker.failed(slot, e, Instant::now()),
+            }
+
+            for outcome in tracker.pop_settled() {
+                match outcome {
+                    SlotOutcome::Block(block) => {
+                        if block.commitment != Commitment::Finalized {
+                            finality.provisional(&block);
                         }
+                        block_update_sender
+                            .send(BlockUpdate::Block(block))
+                            .expect("channel");
                     }
+                    SlotOutcome::Skipped(_) => {}
+                    SlotOutcome::GaveUp(missed) => block_update_sender
+                        .send(BlockUpdate::Missed(missed))
+                        .expect("channel"),
                 }
+            }
+        }
+    }
 
-                Ok::<_, anyhow::Error>(())
+    // Sends what became of the blocks sent below finalized, up to the
+    // finalized slot, and fetches the blocks finalized in place of dropped ones.
+    // Failures are left for the next check.
+    async fn check_finality(
+        self: &Arc<Self>,
+        tracker: &mut SlotTracker,
+        finality: &mut FinalityTracker,
+        block_update_sender: &UnboundedSender<BlockUpdate>,
+    ) {
+        let Some(first_slot) = finality.first_slot() else {
+            return;
+        };
+        let waiting = finality.slots_through(u64::MAX);
+        let block_gateway = Arc::clone(self);
+
+        let checked = tokio::task::spawn_blocking(move || {
+            let finalized_slot = block_gateway.get_slot()?;
+            if finalized_slot < first_slot {
+                return Ok(None);
             }
-        });
 
-        Ok(())
+            let finalized_blocks = block_gateway.get_blocks(first_slot, finalized_slot)?;
+            let finalized_blockhashes = waiting
+                .into_iter()
+                .filter(|slot| finalized_blocks.contains(slot))
+                .map(|slot| Ok((slot, block_gateway.fetch_finalized_blockhash(slot)?)))
+                .collect::<Result<HashMap<u64, String>, String>>()?;
+
+            Ok(Some((finalized_slot, finalized_blockhashes)))
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|checked| checked);
+
+        match checked {
+            Ok(Some((finalized_slot, finalized_blockhashes))) => {
+                for update in finality.settle(finalized_slot, &finalized_blockhashes) {
+                    if update.finality == Finality::Dropped
+                        && finalized_blockhashes.contains_key(&update.slot)
+                    {
+                        tracker.refetch(update.slot, Instant::now());
+                    }
+                    block_update_sender
+                        .send(BlockUpdate::Finality(update))
+                        .expect("channel");
+                }
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("Error checking finality: {}", e),
+        }
     }
 
-    fn get_block(&self, block: u64) -> Result<Block, String> {
+    // The blockhash alone, to tell whether the block finalized in a slot is
+    // the one that was sent.
+    fn fetch_finalized_blockhash(&self, slot: u64) -> Result<String, String> {
         let client = RpcClient::new(&self.rpc_url);
         let rpc_block_config = RpcBlockConfig {
-            transaction_details: Some(TransactionDetails::Full),
+            transaction_details: Some(TransactionDetails::None),
             commitment: Some(CommitmentConfig::finalized()),
             max_supported_transaction_version: Some(0),
+            encoding: None,
+            rewards: Some(false),
+        };
+
+        client
+            .get_block_with_config(slot, rpc_block_config)
+            .map(|block| block.blockhash)
+            .map_err(|e| e.to_string())
+    }
+
+    // Follows the slot updates of one connection until unsubscribed, or until
+    // the connection closes or stalls, returning why.
+    async fn follow(
+        self: &Arc<Self>,
+        mut slot_updates_notifications: BoxStream<'_, SlotUpdate>,
+        tracker: &mut SlotTracker,
+        finality: &mut FinalityTracker,
+        stop_receiver: &mut oneshot::Receiver<()>,
+        block_update_sender: &UnboundedSender<BlockUpdate>,
+    ) -> Result<(), String> {
+        let mut last_update = Instant::now();
+        let mut next_finality_check = Instant::now();
+
+        loop {
+            self.fetch_due(tracker, finality, block_update_sender).await;
+            if Instant::now() >= next_finality_check {
+                self.check_finality(tracker, finality, block_update_sender)
+                    .await;
+                next_finality_check = Instant::now() + FINALITY_CHECK_INTERVAL;
+            }
+
+            // Slots still due after a full batch are fetched right away, once
+            // the stop signal and the updates that queued up are looked at.
+            let retry_at = if tracker.due(Instant::now(), 1).is_empty() {
+                tracker.next_retry()
+            } else {
+                Some(Instant::now())
+            };
+            // Updates that queued up while fetching go first, so they are not
+            // mistaken for a stall.
+            tokio::select! {
+                biased;
+                _ = &mut *stop_receiver => return Ok(()),
+                slot_info = slot_updates_notifications.next() => match slot_info {
+                    Some(slot_info) => {
+                        last_update = Instant::now();
+                        if let SlotUpdate::Completed { slot, timestamp: _ } = slot_info {
+                            tracker.complete(slot);
+                        }
+                    }
+                    None => return Err("the connection closed".to_string()),
+                },
+                _ = tokio::time::sleep_until(retry_at.unwrap_or_else(Instant::now).into()),
+                    if retry_at.is_some() => {}
+                _ = tokio::time::sleep_until((last_update + self.stall_timeout).into()) => {
+                    return Err(format!(
+                        "no slot update for {} seconds",
+                        self.stall_timeout.as_secs()
+                    ));
+                }
+            }
+        }
+    }
+
+    // The block of the slot, or none when the slot was skipped by its leader.
+    fn fetch_block(&self, slot: u64, commitment: Commitment) -> Result<Option<Block>, String> {
+        let client = RpcClient::new(&self.rpc_url);
+        let rpc_block_config = RpcBlockConfig {
+            transaction_details: Some(TransactionDetails::Full),
+            commitment: Some(match commitment {
+                Commitment::Finalized => CommitmentConfig::finalized(),
+                _ => CommitmentConfig::confirmed(),
+            }),
+            max_supported_transaction_version: Some(0),
             encoding: Some(UiTransactionEncoding::Json),
             rewards: Some(false),
         };
 
-        match client.get_block_with_config(block, rpc_block_config) {
+        match client.get_block_with_config(slot, rpc_block_config) {
             Ok(confirmed_block) => {
-                let mut block = Block::new(block, confirmed_block.blockhash);
+                let mut block = Block::new(slot, confirmed_block.blockhash);
+                block.update_block_time(confirmed_block.block_time);
+                block.update_commitment(commitment);
                 let transactions = confirmed_block.transactions.unwrap();
 
                 for transaction_with_meta in transactions {
                     let meta = transaction_with_meta.meta.unwrap();
 
-                    if let Err(_) = &meta.status {
+                    if meta.status.is_err() {
                         continue;
                     }
 
                     if let EncodedTransaction::Json(transaction) =
                         &transaction_with_meta.transaction
                     {
-                        let accounts_by_index = self.get_accounts(&meta);
+                        self.add_transaction_to_block(&mut block, &meta, transaction, &self.mints);
+                    }
+                }
 
-                        if accounts_by_index.len() == 0 {
-                            continue;
+                Ok(Some(block))
+            }
+            Err(e) if is_skipped(&e) => Ok(None),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn add_transaction_to_block(
+        &self,
+        block: &mut Block,
+        meta: &UiTransactionStatusMeta,
+        transaction: &UiTransaction,
+        mints: &[Mint],
+    ) {
+        for mint in mints {
+            self.add_to_block(block, meta, transaction, mint);
+
+            if mint.is_native() {
+                self.add_native_to_block(block, meta, transaction, mint);
+            }
+        }
+    }
+
+    fn add_to_block(
+        &self,
+        block: &mut Block,
+        meta: &UiTransactionStatusMeta,
+        transaction: &UiTransaction,
+        mint: &Mint,
+    ) {
+        let accounts_by_index = self.get_accounts(meta, mint);
+
+        // A wrapped SOL account created and closed in the transaction has no
+        // token balance, so the native mint is looked for without any.
+        if accounts_by_index.is_empty() && !mint.is_native() {
+            return;
+        }
+
+        let addresses = self.get_addresses(meta, transaction);
+        let program = match self.get_program(meta, &addresses, mint) {
+            Some(program) => program,
+            None if mint.is_native() => match addresses
+                .iter()
+                .position(|address| address == TOKEN_PROGRAM_ID)
+            {
+                Some(index) => Program::new(TOKEN_PROGRAM_ID, index as u8),
+                None => return,
+            },
+            None => return,
+        };
+        let accounts_by_address: HashMap<&str, &Account> = accounts_by_index
+            .values()
+            .filter_map(|account| {
+                let address = addresses.get(account.index as usize)?;
+                Some((address.as_str(), account))
+            })
+            .collect();
+        let signature = transaction.signatures.first().unwrap();
+        let events = self.get_events(&program, meta, transaction, &addresses);
+        let mut transfers_by_destination: HashMap<&str, usize> = HashMap::new();
+        let wrapped = if mint.is_native() {
+            let known = accounts_by_address
+                .keys()
+                .map(|address| address.to_string())
+                .collect();
+            let kinds: Vec<EventKind> = events.iter().map(|(_, kind)| kind.clone()).collect();
+            wrapped_sol::wrapped_accounts(&kinds, &known)
+        } else {
+            HashSet::new()
+        };
+
+        for (_, destination) in events.iter().filter_map(|(_, kind)| kind.transfer()) {
+            *transfers_by_destination.entry(destination).or_default() += 1;
+        }
+
+        for (instruction_index, kind) in events.iter().cloned() {
+            let involves_mint = kind.accounts().iter().any(|address| {
+                *address == mint.address
+                    || accounts_by_address.contains_key(address)
+                    || wrapped.contains(*address)
+            });
+
+            if !involves_mint {
+                continue;
+            }
+
+            if let (Some((source, destination)), Some(amount)) = (kind.transfer(), kind.amount()) {
+                if let (Some(source_account), Some(destination_account)) = (
+                    accounts_by_address.get(source),
+                    accounts_by_address.get(destination),
+                ) {
+                    let decimals = destination_account.pre_balance.decimals;
+                    let mut transaction = Transaction::new(
+                        signature.to_owned(),
+                        (*source_account).clone(),
+                        (*destination_account).clone(),
+                        program.clone(),
+                        mint.clone(),
+                        Amount::new(amount, decimals),
+                    );
+                    transaction.update_instruction_index(instruction_index);
+
+                    if let Some(fee) = kind.fee() {
+                        transaction.update_fee(Amount::new(fee, decimals));
+                    } else if program.kind == ProgramKind::Token2022
+                        && transfers_by_destination.get(destination) == Some(&1)
+                    {
+                        // A plain transfer on a mint with a transfer fee credits the
+                        // destination with less than the amount, the rest is withheld.
+                        let received = destination_account
+                            .post_balance
+                            .saturating_sub(destination_account.pre_balance);
+
+                        if let Some(fee) = Amount::new(amount, decimals).checked_sub(received) {
+                            if !fee.is_zero() && !received.is_zero() {
+                                transaction.update_fee(fee);
+                            }
+                        }
+                    }
+
+                    block.add_transaction(transaction);
+                }
+            }
+
+            // Closing a wrapped SOL account unwraps the lamports it holds at that
+            // point to the destination. The owner is the one in the token balance
+            // when there is one, the account that signed the close otherwise.
+            if let EventKind::CloseAccount {
+                account,
+                destination,
+                owner,
+            } = &kind
+            {
+                let lamport_accounts = self.get_lamport_accounts(meta, &addresses);
+                let find = |address: &str| {
+                    let index = addresses.iter().position(|other| other == address)?;
+                    lamport_accounts.get(&(index as u8))
+                };
+
+                if let (true, Some(closed), Some(receiver)) =
+                    (wrapped.contains(account), find(account), find(destination))
+                {
+                    let lamports =
+                        self.get_closed_lamports(&program, meta, transaction, &addresses, closed);
+                    let mut source_account = closed.clone();
+                    source_account.address = match accounts_by_address.get(account.as_str()) {
+                        Some(balance) => balance.address.clone(),
+                        None => owner.clone(),
+                    };
+
+                    let mut transaction = Transaction::new(
+                        signature.to_owned(),
+                        source_account,
+                        receiver.clone(),
+                        program.clone(),
+                        mint.clone(),
+                        Amount::new(lamports, NATIVE_DECIMALS),
+                    );
+                    transaction.update_instruction_index(instruction_index);
+
+                    block.add_transaction(transaction);
+                }
+            }
+
+            block.add_event(Event::new(signature.to_owned(), program.clone(), kind));
+        }
+    }
+
+    // The lamports the closed account held when it was closed, from the token
+    // and System program transfers of the transaction.
+    fn get_closed_lamports(
+        &self,
+        program: &Program,
+        meta: &UiTransactionStatusMeta,
+        transaction: &UiTransaction,
+        addresses: &[String],
+        closed: &Account,
+    ) -> u64 {
+        let mut events: Vec<EventKind> = self
+            .get_events(program, meta, transaction, addresses)
+            .into_iter()
+            .map(|(_, kind)| kind)
+            .collect();
+
+        if let Some(index) = addresses
+            .iter()
+            .position(|address| address == SYSTEM_PROGRAM_ID)
+        {
+            let system_program = Program::new(SYSTEM_PROGRAM_ID, index as u8);
+            events.extend(
+                self.get_events(&system_program, meta, transaction, addresses)
+                    .into_iter()
+                    .map(|(_, kind)| kind),
+            );
+        }
+
+        wrapped_sol::closed_lamports(
+            &events,
+            &closed.address,
+            closed.pre_balance.raw,
+            closed.post_balance.raw,
+        )
+    }
+
+    fn add_native_to_block(
+        &self,
+        block: &mut Block,
+        meta: &UiTransactionStatusMeta,
+        transaction: &UiTransaction,
+        mint: &Mint,
+    ) {
+        let addresses = self.get_addresses(meta, transaction);
+        let program = match addresses
+            .iter()
+            .position(|address| address == SYSTEM_PROGRAM_ID)
+        {
+            Some(index) => Program::new(SYSTEM_PROGRAM_ID, index as u8),
+            None => return,
+        };
+        let accounts_by_index = self.get_lamport_accounts(meta, &addresses);
+        let signature = transaction.signatures.first().unwrap();
+
+        for (instruction_index, kind) in self.get_events(&program, meta, transaction, &addresses) {
+            if let (Some((source, destination)), Some(lamports)) = (kind.transfer(), kind.amount())
+            {
+                let source_account = addresses
+                    .iter()
+                    .position(|address| address == source)
+                    .and_then(|index| accounts_by_index.get(&(index as u8)));
+                let destination_account = addresses
+                    .iter()
+                    .position(|address| address == destination)
+                    .and_then(|index| accounts_by_index.get(&(index as u8)));
+
+                if let (Some(source_account), Some(destination_account)) =
+                    (source_account, destination_account)
+                {
+                    let mut transaction = Transaction::new(
+                        signature.to_owned(),
+                        source_account.clone(),
+                        destination_account.clone(),
+                        program.clone(),
+                        mint.clone(),
+                        Amount::new(lamports, NATIVE_DECIMALS),
+                    );
+                    transaction.update_instruction_index(instruction_index);
+
+                    block.add_transaction(transaction);
+                }
+            }
+
+            block.add_event(Event::new(signature.to_owned(), program.clone(), kind));
+        }
+    }
+}
+
+impl BlockGateway for BlockGatewayImpl {
+    async fn subscribe(
+        self: Arc<Self>,
+        start_slot: Option<u64>,
+        ready_sender: &UnboundedSender<Result<(), String>>,
+        unsubscribe_sender: &UnboundedSender<Unsubscribe>,
+        block_update_sender: &UnboundedSender<BlockUpdate>,
+    ) -> Result<()> {
+        let pubsub_client = PubsubClient::new(self.websocket_url.as_str()).await?;
+        let (stop_sender, mut stop_receiver) = oneshot::channel::<()>();
+
+        // Slots the node no longer has cannot be caught up on.
+        let start_slot = match start_slot {
+            Some(start_slot) => {
+                let block_gateway = Arc::clone(&self);
+                let first_available_block =
+                    tokio::task::spawn_blocking(move || block_gateway.get_first_available_block())
+                        .await?
+                        .map_err(|e| anyhow!(e))?;
+                if start_slot < first_available_block {
+                    eprintln!(
+                        "Slots before {} are no longer available, starting from there",
+                        first_available_block
+                    );
+                }
+                Some(start_slot.max(first_available_block))
+            }
+            None => None,
+        };
+
+        // Unsubscribing stops the task below, whichever connection it is on.
+        unsubscribe_sender
+            .send(Box::new(move || {
+                Box::pin(async move {
+                    let _ = stop_sender.send(());
+                })
+            }))
+            .map_err(|_| "channel closed")
+            .expect("channel");
+
+        tokio::spawn({
+            let ready_sender = ready_sender.clone();
+            let block_update_sender = block_update_sender.clone();
+
+            async move {
+                let mut ready_sender = Some(ready_sender);
+                let mut pubsub_client = Some(pubsub_client);
+                // Kept across connections, so the slots missed while disconnected
+                // are fetched once updates come in again. When resuming, the first
+                // update opens a gap from the start slot, caught up on the same way.
+                let mut tracker = SlotTracker::new(start_slot, RETRY_BACKOFF);
+                let mut finality = FinalityTracker::new();
+                let mut attempt = 0;
+
+                loop {
+                    if attempt > 0 {
+                        let delay = RECONNECT_BACKOFF.delay(attempt);
+                        let state = ConnectionState::Reconnecting {
+                            attempt,
+                            delay_seconds: delay.as_secs(),
+                        };
+                        block_update_sender
+                            .send(BlockUpdate::Connection(state))
+                            .expect("channel");
+
+                        tokio::select! {
+                            _ = &mut stop_receiver => return,
+                            _ = tokio::time::sleep(delay) => {}
                         }
+                    }
 
-                        if let Some(program) = self.get_program(&meta, &transaction) {
-                            let account_pairs =
-                                self.get_account_pairs(&program, &meta, &transaction);
+                    let connection = match pubsub_client.take() {
+                        Some(pubsub_client) => Ok(pubsub_client),
+                        None => PubsubClient::new(self.websocket_url.as_str()).await,
+                    };
+                    let subscription = match &connection {
+                        Ok(pubsub_client) => pubsub_client
+                            .slot_updates_subscribe()
+                            .await
+                            .map_err(|e| e.to_string()),
+                        Err(e) => Err(e.to_string()),
+                    };
+                    let reason = match subscription {
+                        Ok((slot_updates_notifications, slot_updates_unsubscribe)) => {
+                            attempt = 0;
+                            block_update_sender
+                                .send(BlockUpdate::Connection(ConnectionState::Connected))
+                                .expect("channel");
+
+                            // With the first subscription started,
+                            // send a signal back to the main task for synchronization.
+                            if let Some(ready_sender) = ready_sender.take() {
+                                ready_sender.send(Ok(())).expect("channel");
+                            }
 
-                            if account_pairs.len() == 0 {
-                                continue;
+                            match self
+                                .follow(
+                                    slot_updates_notifications,
+                                    &mut tracker,
+                                    &mut finality,
+                                    &mut stop_receiver,
+                                    &block_update_sender,
+                                )
+                                .await
+                            {
+                                Ok(()) => {
+                                    slot_updates_unsubscribe().await;
+                                    return;
+                                }
+                                Err(reason) => reason,
                             }
+                        }
+                        // Without a first subscription, there is nothing to resume,
+                        // the caller gets the error instead.
+                        Err(e) => match ready_sender.take() {
+                            Some(ready_sender) => {
+                                ready_sender.send(Err(e)).expect("channel");
+                                return;
+                            }
+                            None => e,
+                        },
+                    };
+
+                    attempt += 1;
+                    block_update_sender
+                        .send(BlockUpdate::Connection(ConnectionState::Disconnected {
+                            reason,
+                        }))
+                        .expect("channel");
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    fn get_block(&self, block: u64) -> Result<Block, String> {
+        self.fetch_block(block, self.block_commitment())?
+            .ok_or_else(|| format!("Slot {} was skipped, it has no block", block))
+    }
+
+    fn get_transaction(&self, signature: &str) -> Result<TransactionDetail, String> {
+        let client = RpcClient::new(&self.rpc_url);
+        let signature = Signature::from_str(signature).map_err(|e| e.to_string())?;
+        let rpc_transaction_config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            commitment: Some(CommitmentConfig::finalized()),
+            max_supported_transaction_version: Some(0),
+        };
+
+        let confirmed_transaction = client
+            .get_transaction_with_config(&signature, rpc_transaction_config)
+            .map_err(|e| e.to_string())?;
+        let meta = confirmed_transaction
+            .transaction
+            .meta
+            .ok_or("Transaction has no status meta")?;
+        let transaction = match &confirmed_transaction.transaction.transaction {
+            EncodedTransaction::Json(transaction) => transaction,
+            _ => return Err("Unexpected transaction encoding".to_string()),
+        };
+        let addresses = self.get_addresses(&meta, transaction);
+
+        let mut detail = TransactionDetail::new(
+            signature.to_string(),
+            confirmed_transaction.slot,
+            addresses.first().cloned().unwrap_or_default(),
+            Amount::new(meta.fee, NATIVE_DECIMALS),
+        );
+        detail.block_time = confirmed_transaction.block_time;
+        detail.error = meta.status.as_ref().err().map(|e| e.to_string());
+        detail.compute_units = meta.compute_units_consumed.clone().into();
+        detail.logs = Option::from(meta.log_messages.clone()).unwrap_or_default();
+        detail.instructions = self.get_instruction_tree(&meta, transaction, &addresses);
+
+        // Failed transactions are rolled back, so only successful ones moved funds.
+        if detail.error.is_none() {
+            let mut mints: Vec<Mint> = Vec::new();
+            let balances = [&meta.pre_token_balances, &meta.post_token_balances];
 
-                            account_pairs.iter().for_each(|(source, destination)| {
-                                let source_account = match accounts_by_index.get(source) {
-                                    Some(account) => account.clone(),
-                                    None => return,
-                                };
-                                let destination_account = match accounts_by_index.get(destination) {
-                                    Some(account) => account.clone(),
-                                    None => return,
-                                };
-
-                                let transaction = Transaction::new(
-                                    transaction.signatures.get(0).unwrap().to_owned(),
-                                    source_account,
-                                    destination_account,
-                                    program.clone(),
-                                    USDC_MINT.to_owned(),
-                                );
-
-                                block.add_transaction(transaction);
-                            });
+            for token_balances in balances {
+                if let OptionSerializer::Some(token_balances) = token_balances {
+                    for balance in token_balances {
+                        if !mints.iter().any(|mint| mint.address == balance.mint) {
+                            mints.push(Mint::new(&balance.mint));
                         }
                     }
                 }
+            }
 
-                return Ok(block);
+            if !mints.iter().any(|mint| mint.is_native()) {
+                mints.push(Mint::new(NATIVE_MINT));
             }
-            Err(e) => {
-                return Err(e.to_string());
+
+            let mut block = Block::new(detail.slot, String::new());
+            self.add_transaction_to_block(&mut block, &meta, transaction, &mints);
+
+            detail.transactions = block.transactions;
+            detail.events = block.events;
+        }
+
+        Ok(detail)
+    }
+
+    fn get_slot(&self) -> Result<u64, String> {
+        let client = RpcClient::new(&self.rpc_url);
+
+        client
+            .get_slot_with_commitment(CommitmentConfig::finalized())
+            .map_err(|e| e.to_string())
+    }
+
+    fn get_first_available_block(&self) -> Result<u64, String> {
+        let client = RpcClient::new(&self.rpc_url);
+
+        client
+            .get_first_available_block()
+            .map_err(|e| e.to_string())
+    }
+
+    fn get_blocks(&self, start_slot: u64, end_slot: u64) -> Result<Vec<u64>, String> {
+        let client = RpcClient::new(&self.rpc_url);
+        let mut blocks: Vec<u64> = Vec::new();
+        let mut start = start_slot;
+
+        // getBlocks only accepts ranges of up to 500,000 slots.
+        while start <= end_slot {
+            let end = end_slot.min(start.saturating_add(MAX_GET_BLOCKS_RANGE - 1));
+            let mut chunk = client
+                .get_blocks_with_commitment(start, Some(end), CommitmentConfig::finalized())
+                .map_err(|e| e.to_string())?;
+
+            blocks.append(&mut chunk);
+
+            if end == u64::MAX {
+                break;
             }
+            start = end + 1;
         }
+
+        Ok(blocks)
+    }
+
+    fn get_blocks_with_limit(&self, start_slot: u64, limit: usize) -> Result<Vec<u64>, String> {
+        let client = RpcClient::new(&self.rpc_url);
+
+        client
+            .get_blocks_with_limit_and_commitment(start_slot, limit, CommitmentConfig::finalized())
+            .map_err(|e| e.to_string())
+    }
+
+    fn get_block_time(&self, slot: u64) -> Result<i64, String> {
+        let client = RpcClient::new(&self.rpc_url);
+
+        client.get_block_time(slot).map_err(|e| e.to_string())
+    }
+
+    fn get_signatures_for_address(
+        &self,
+        address: &str,
+        before: Option<String>,
+        limit: usize,
+    ) -> Result<Vec<String>, String> {
+        let client = RpcClient::new(&self.rpc_url);
+        let address = Pubkey::from_str(address).map_err(|e| e.to_string())?;
+        let before = match before {
+            Some(before) => Some(Signature::from_str(&before).map_err(|e| e.to_string())?),
+            None => None,
+        };
+        let config = GetConfirmedSignaturesForAddress2Config {
+            before,
+            until: None,
+            limit: Some(limit),
+            commitment: Some(CommitmentConfig::finalized()),
+        };
+
+        client
+            .get_signatures_for_address_with_config(&address, config)
+            .map(|statuses| {
+                statuses
+                    .into_iter()
+                    .map(|status| status.signature)
+                    .collect()
+            })
+            .map_err(|e| e.to_string())
+    }
+
+    // Associated token accounts of the owner for every tracked mint, under both
+    // token programs. Native SOL is held by the owner itself.
+    fn get_token_accounts(&self, owner: &str) -> Result<Vec<String>, String> {
+        let owner = Pubkey::from_str(owner).map_err(|e| e.to_string())?;
+        let associated_token_program = Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM_ID).unwrap();
+        let token_programs = [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID]
+            .map(|program_id| Pubkey::from_str(program_id).unwrap());
+        let mut token_accounts: Vec<String> = Vec::new();
+
+        for mint in &self.mints {
+            let mint = Pubkey::from_str(&mint.address).map_err(|e| e.to_string())?;
+
+            for token_program in &token_programs {
+                let (token_account, _) = Pubkey::find_program_address(
+                    &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+                    &associated_token_program,
+                );
+
+                token_accounts.push(token_account.to_string());
+            }
+        }
+
+        Ok(token_accounts)
     }
 }
@@ -0,0 +1,65 @@
+use crate::application::gateways::checkpoint::Checkpoint;
+use anyhow::{anyhow, Context, Result};
+use std::path::PathBuf;
+
+// Keeps the slot as text in a file, replaced as a whole on every save so a
+// crash never leaves it half written.
+pub struct FileCheckpoint {
+    path: PathBuf,
+}
+
+impl FileCheckpoint {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Checkpoint for FileCheckpoint {
+    fn load(&self) -> Result<Option<u64>> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("reading checkpoint from {}", self.path.display()))
+            }
+        };
+
+        content.trim().parse().map(Some).map_err(|_| {
+            anyhow!(
+                "invalid checkpoint {}: {:?} is not a slot",
+                self.path.display(),
+                content.trim()
+            )
+        })
+    }
+
+    fn save(&mut self, slot: u64) -> Result<()> {
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+
+        std::fs::write(&temporary, format!("{}\n", slot))
+            .and_then(|_| std::fs::rename(&temporary, &self.path))
+            .with_context(|| format!("writing checkpoint to {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_checkpoint() {
+        let path = std::env::temp_dir().join(format!("checkpoint-{}", std::process::id()));
+        let mut checkpoint = FileCheckpoint::new(&path);
+
+        assert_eq!(checkpoint.load().unwrap(), None);
+        checkpoint.save(268_000_000).unwrap();
+        checkpoint.save(268_000_001).unwrap();
+        assert_eq!(checkpoint.load().unwrap(), Some(268_000_001));
+
+        std::fs::write(&path, "nope").unwrap();
+        assert!(checkpoint.load().is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1 +1,4 @@
+pub mod alert_sinks;
 pub mod block_gateway;
+pub mod checkpoint_file;
+pub mod sqlite_store;
@@ -0,0 +1,496 @@
+use crate::application::gateways::block_store::BlockStore;
+use crate::domain::amount::{Amount, Volume};
+use crate::domain::block::Block;
+use crate::domain::mint::Mint;
+use crate::domain::report::{HourlyVolume, SenderTotal, StoredTransfer};
+use anyhow::{anyhow, Context, Result};
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Row, Transaction};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::time::Duration;
+
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Applied in order, the schema version being the number of migrations run,
+// kept in `PRAGMA user_version`. Only ever append to this list.
+const MIGRATIONS: [&str; 1] = [r#"
+    CREATE TABLE blocks (
+        slot INTEGER PRIMARY KEY,
+        blockhash TEXT NOT NULL,
+        block_time INTEGER
+    );
+    CREATE TABLE accounts (
+        id INTEGER PRIMARY KEY,
+        address TEXT NOT NULL UNIQUE
+    );
+    CREATE TABLE mints (
+        id INTEGER PRIMARY KEY,
+        address TEXT NOT NULL UNIQUE,
+        decimals INTEGER NOT NULL
+    );
+    CREATE TABLE transactions (
+        signature TEXT PRIMARY KEY,
+        slot INTEGER NOT NULL REFERENCES blocks (slot)
+    );
+    CREATE TABLE transfers (
+        signature TEXT NOT NULL REFERENCES transactions (signature),
+        instruction_index INTEGER NOT NULL,
+        source_id INTEGER NOT NULL REFERENCES accounts (id),
+        destination_id INTEGER NOT NULL REFERENCES accounts (id),
+        program_id INTEGER NOT NULL REFERENCES accounts (id),
+        mint_id INTEGER NOT NULL REFERENCES mints (id),
+        amount TEXT NOT NULL,
+        fee TEXT,
+        PRIMARY KEY (signature, instruction_index)
+    );
+    CREATE INDEX transactions_slot ON transactions (slot);
+    CREATE INDEX transfers_source ON transfers (source_id, mint_id);
+    CREATE INDEX transfers_destination ON transfers (destination_id, mint_id);
+"#];
+
+// Blocks, transactions and the accounts and mints they involve, normalized
+// into a SQLite database. Raw amounts go above i64::MAX, SQLite's largest
+// integer, so they are kept as decimal text and only ever added up in Rust.
+pub struct SqliteStore {
+    connection: Connection,
+}
+
+impl SqliteStore {
+    pub fn open(path: &Path) -> Result<Self> {
+        let connection = Connection::open(path)
+            .with_context(|| format!("opening database {}", path.display()))?;
+        // Lets `query` read while `track` writes.
+        connection.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+
+        Self::new(connection).with_context(|| format!("in database {}", path.display()))
+    }
+
+    fn new(mut connection: Connection) -> Result<Self> {
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+        connection.pragma_update(None, "foreign_keys", true)?;
+        migrate(&mut connection)?;
+
+        Ok(Self { connection })
+    }
+}
+
+fn migrate(connection: &mut Connection) -> Result<()> {
+    let version: usize = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+    if version > MIGRATIONS.len() {
+        return Err(anyhow!(
+            "schema version {} is newer than this build knows ({})",
+            version,
+            MIGRATIONS.len()
+        ));
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let transaction = connection.transaction()?;
+        transaction.execute_batch(migration)?;
+        transaction.pragma_update(None, "user_version", index + 1)?;
+        transaction.commit()?;
+    }
+
+    Ok(())
+}
+
+fn account_id(transaction: &Transaction, address: &str) -> Result<i64> {
+    transaction
+        .prepare_cached(
+            "INSERT INTO accounts (address) VALUES (?1) ON CONFLICT (address) DO NOTHING",
+        )?
+        .execute(params![address])?;
+
+    Ok(transaction
+        .prepare_cached("SELECT id FROM accounts WHERE address = ?1")?
+        .query_row(params![address], |row| row.get(0))?)
+}
+
+fn mint_id(transaction: &Transaction, token: &Mint, decimals: u8) -> Result<i64> {
+    transaction
+        .prepare_cached(
+            "INSERT INTO mints (address, decimals) VALUES (?1, ?2)
+             ON CONFLICT (address) DO UPDATE SET decimals = excluded.decimals",
+        )?
+        .execute(params![token.address, decimals])?;
+
+    Ok(transaction
+        .prepare_cached("SELECT id FROM mints WHERE address = ?1")?
+        .query_row(params![token.address], |row| row.get(0))?)
+}
+
+fn delete_slot(transaction: &Transaction, slot: u64) -> Result<()> {
+    transaction.execute(
+        "DELETE FROM transfers WHERE signature IN (
+             SELECT signature FROM transactions WHERE slot = ?1
+         )",
+        params![slot],
+    )?;
+    transaction.execute("DELETE FROM transactions WHERE slot = ?1", params![slot])?;
+    transaction.execute("DELETE FROM blocks WHERE slot = ?1", params![slot])?;
+
+    Ok(())
+}
+
+fn amount(row: &Row, column: &str, decimals: u8) -> rusqlite::Result<Amount> {
+    let raw: String = row.get(column)?;
+
+    Amount::parse(&raw, decimals).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(
+            row.as_ref().column_index(column).unwrap_or_default(),
+            Type::Text,
+            e.into(),
+        )
+    })
+}
+
+fn fee(row: &Row, column: &str, decimals: u8) -> rusqlite::Result<Option<Amount>> {
+    match row.get::<_, Option<String>>(column)? {
+        Some(_) => amount(row, column, decimals).map(Some),
+        None => Ok(None),
+    }
+}
+
+impl BlockStore for SqliteStore {
+    fn save(&mut self, block: &Block) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        // Transfers of an earlier version of the block must not linger.
+        delete_slot(&transaction, block.slot)?;
+
+        transaction
+            .prepare_cached(
+                "INSERT INTO blocks (slot, blockhash, block_time) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (slot) DO UPDATE
+                 SET blockhash = excluded.blockhash, block_time = excluded.block_time",
+            )?
+            .execute(params![block.slot, block.blockhash, block.block_time])?;
+
+        for transfer in &block.transactions {
+            transaction
+                .prepare_cached(
+                    "INSERT INTO transactions (signature, slot) VALUES (?1, ?2)
+                     ON CONFLICT (signature) DO UPDATE SET slot = excluded.slot",
+                )?
+                .execute(params![transfer.signature, block.slot])?;
+
+            let source_id = account_id(&transaction, &transfer.source.address)?;
+            let destination_id = account_id(&transaction, &transfer.destination.address)?;
+            let program_id = account_id(&transaction, &transfer.program.address)?;
+            let mint_id = mint_id(&transaction, &transfer.token, transfer.amount.decimals)?;
+
+            transaction
+                .prepare_cached(
+                    "INSERT INTO transfers (signature, instruction_index, source_id,
+                         destination_id, program_id, mint_id, amount, fee)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                     ON CONFLICT (signature, instruction_index) DO UPDATE
+                     SET source_id = excluded.source_id,
+                         destination_id = excluded.destination_id,
+                         program_id = excluded.program_id,
+                         mint_id = excluded.mint_id,
+                         amount = excluded.amount,
+                         fee = excluded.fee",
+                )?
+                .execute(params![
+                    transfer.signature,
+                    transfer.instruction_index,
+                    source_id,
+                    destination_id,
+                    program_id,
+                    mint_id,
+                    transfer.amount.raw.to_string(),
+                    transfer.fee.map(|fee| fee.raw.to_string()),
+                ])?;
+        }
+
+        Ok(transaction.commit()?)
+    }
+
+    fn remove(&mut self, slot: u64) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        delete_slot(&transaction, slot)?;
+
+        Ok(transaction.commit()?)
+    }
+
+    fn top_senders(&self, token: Option<&Mint>, limit: usize) -> Result<Vec<SenderTotal>> {
+        let mut statement = self.connection.prepare(
+            "SELECT a.address AS address, m.address AS mint, m.decimals AS decimals,
+                 t.amount AS amount
+             FROM transfers t
+             JOIN accounts a ON a.id = t.source_id
+             JOIN mints m ON m.id = t.mint_id
+             WHERE ?1 IS NULL OR m.address = ?1",
+        )?;
+        let mut rows = statement.query(params![token.map(|token| &token.address)])?;
+
+        let mut totals: BTreeMap<(String, String), (u64, Volume)> = BTreeMap::new();
+        while let Some(row) = rows.next()? {
+            let decimals = row.get("decimals")?;
+            let key = (row.get("mint")?, row.get("address")?);
+            let (transfers, volume) = totals.entry(key).or_insert((0, Volume::new(0, decimals)));
+            *transfers += 1;
+            volume.add(amount(row, "amount", decimals)?);
+        }
+
+        let mut senders: Vec<SenderTotal> = totals
+            .into_iter()
+            .map(|((mint, address), (transfers, volume))| SenderTotal {
+                address,
+                token: Mint::new(mint),
+                transfers,
+                volume,
+            })
+            .collect();
+        // Largest first within each mint.
+        senders.sort_by(|a, b| {
+            a.token
+                .address
+                .cmp(&b.token.address)
+                .then_with(|| b.volume.raw.cmp(&a.volume.raw))
+                .then_with(|| a.address.cmp(&b.address))
+        });
+
+        let mut ranks: HashMap<String, usize> = HashMap::new();
+        senders.retain(|sender| {
+            let rank = ranks.entry(sender.token.address.clone()).or_default();
+            *rank += 1;
+            *rank <= limit
+        });
+
+        Ok(senders)
+    }
+
+    fn volume_by_hour(&self, token: Option<&Mint>, limit: usize) -> Result<Vec<HourlyVolume>> {
+        let mut statement = self.connection.prepare(
+            "SELECT b.block_time / 3600 * 3600 AS hour, m.address AS mint,
+                 m.decimals AS decimals, t.amount AS amount
+             FROM transfers t
+             JOIN transactions x ON x.signature = t.signature
+             JOIN blocks b ON b.slot = x.slot
+             JOIN mints m ON m.id = t.mint_id
+             WHERE b.block_time IS NOT NULL AND (?1 IS NULL OR m.address = ?1)",
+        )?;
+        let mut rows = statement.query(params![token.map(|token| &token.address)])?;
+
+        let mut hours: BTreeMap<(i64, String), (u64, Volume)> = BTreeMap::new();
+        while let Some(row) = rows.next()? {
+            let decimals = row.get("decimals")?;
+            let key = (row.get("hour")?, row.get("mint")?);
+            let (transfers, volume) = hours.entry(key).or_insert((0, Volume::new(0, decimals)));
+            *transfers += 1;
+            volume.add(amount(row, "amount", decimals)?);
+        }
+
+        // The `limit` latest hours.
+        let mut latest: Vec<i64> = hours.keys().map(|(hour, _)| *hour).collect();
+        latest.dedup();
+        let first_hour = match latest.len().checked_sub(limit) {
+            Some(index) => latest.get(index).copied().unwrap_or(i64::MAX),
+            None => i64::MIN,
+        };
+
+        Ok(hours
+            .into_iter()
+            .filter(|((hour, _), _)| *hour >= first_hour)
+            .map(|((hour, mint), (transfers, volume))| HourlyVolume {
+                hour,
+                token: Mint::new(mint),
+                transfers,
+                volume,
+            })
+            .collect())
+    }
+
+    fn history(
+        &self,
+        address: &str,
+        token: Option<&Mint>,
+        limit: usize,
+    ) -> Result<Vec<StoredTransfer>> {
+        let Some(account_id) = self
+            .connection
+            .query_row(
+                "SELECT id FROM accounts WHERE address = ?1",
+                params![address],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()?
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut statement = self.connection.prepare(
+            "SELECT b.slot AS slot, b.block_time AS block_time, t.signature AS signature,
+                 t.instruction_index AS instruction_index, s.address AS source,
+                 d.address AS destination, p.address AS program, m.address AS mint,
+                 m.decimals AS decimals, t.amount AS amount, t.fee AS fee
+             FROM transfers t
+             JOIN transactions x ON x.signature = t.signature
+             JOIN blocks b ON b.slot = x.slot
+             JOIN accounts s ON s.id = t.source_id
+             JOIN accounts d ON d.id = t.destination_id
+             JOIN accounts p ON p.id = t.program_id
+             JOIN mints m ON m.id = t.mint_id
+             WHERE (t.source_id = ?1 OR t.destination_id = ?1)
+                 AND (?2 IS NULL OR m.address = ?2)
+             ORDER BY b.slot DESC, t.signature DESC, t.instruction_index DESC
+             LIMIT ?3",
+        )?;
+        let rows = statement.query_map(
+            params![account_id, token.map(|token| &token.address), limit],
+            |row| {
+                let decimals = row.get("decimals")?;
+
+                Ok(StoredTransfer {
+                    slot: row.get("slot")?,
+                    block_time: row.get("block_time")?,
+                    signature: row.get("signature")?,
+                    instruction_index: row.get("instruction_index")?,
+                    source: row.get("source")?,
+                    destination: row.get("destination")?,
+                    program: row.get("program")?,
+                    token: Mint::new(row.get::<_, String>("mint")?),
+                    amount: amount(row, "amount", decimals)?,
+                    fee: fee(row, "fee", decimals)?,
+                })
+            },
+        )?;
+        let mut transfers = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+        transfers.reverse();
+
+        Ok(transfers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::program::Program;
+    use crate::domain::transaction::Transaction as Transfer;
+
+    fn store() -> SqliteStore {
+        SqliteStore::new(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    fn block(slot: u64, block_time: i64, transfers: &[(&str, &str, &str, u64)]) -> Block {
+        let mut block = Block::new(slot, format!("hash-{}", slot));
+        block.update_block_time(Some(block_time));
+        for (index, (source, destination, mint, amount)) in transfers.iter().enumerate() {
+            let mut transfer = Transfer::new(
+                format!("sig-{}", slot),
+                Account::new(*source, 1, Amount::zero(6)),
+                Account::new(*destination, 2, Amount::zero(6)),
+                Program::new("program", 3),
+                mint.parse::<Mint>().unwrap(),
+                Amount::new(*amount, 6),
+            );
+            transfer.update_instruction_index(index);
+            block.add_transaction(transfer);
+        }
+
+        block
+    }
+
+    #[test]
+    fn test_migrations_and_idempotent_save() {
+        let mut store = store();
+        let first = block(1, 7200, &[("alice", "bob", "USDC", 5_000_000)]);
+
+        store.save(&first).unwrap();
+        store.save(&first).unwrap();
+
+        let count = |store: &SqliteStore| -> i64 {
+            store
+                .connection
+                .query_row("SELECT COUNT(*) FROM transfers", [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(count(&store), 1);
+
+        // Saving another version of the block replaces its transfers.
+        store.save(&block(1, 7200, &[])).unwrap();
+        assert_eq!(count(&store), 0);
+        store.save(&first).unwrap();
+
+        store.remove(1).unwrap();
+        store.remove(1).unwrap();
+        assert!(store.history("alice", None, 10).unwrap().is_empty());
+
+        // Opening an up to date database again runs no migration.
+        migrate(&mut store.connection).unwrap();
+        store
+            .connection
+            .pragma_update(None, "user_version", MIGRATIONS.len() + 1)
+            .unwrap();
+        assert!(migrate(&mut store.connection).is_err());
+    }
+
+    #[test]
+    fn test_reports() {
+        let mut store = store();
+        store
+            .save(&block(
+                1,
+                3600,
+                &[
+                    ("alice", "bob", "USDC", 5_000_000),
+                    ("carol", "bob", "USDC", 1_000_000),
+                    ("alice", "carol", "USDT", 2_000_000),
+                ],
+            ))
+            .unwrap();
+        store
+            .save(&block(2, 7300, &[("alice", "dave", "USDC", 3_000_000)]))
+            .unwrap();
+
+        let usdc = "USDC".parse::<Mint>().unwrap();
+        let senders = store.top_senders(Some(&usdc), 1).unwrap();
+        assert_eq!(
+            senders,
+            vec![SenderTotal {
+                address: "alice".to_string(),
+                token: usdc.clone(),
+                transfers: 2,
+                volume: Volume::new(8_000_000, 6),
+            }]
+        );
+        assert_eq!(store.top_senders(None, 10).unwrap().len(), 3);
+
+        let hours = store.volume_by_hour(None, 1).unwrap();
+        assert_eq!(hours.len(), 1);
+        assert_eq!(hours[0].hour, 7200);
+        assert_eq!(hours[0].volume, Volume::new(3_000_000, 6));
+        assert_eq!(store.volume_by_hour(Some(&usdc), 24).unwrap().len(), 2);
+
+        let history = store.history("carol", None, 10).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].instruction_index, 1);
+        assert_eq!(history[1].destination, "carol");
+        assert_eq!(store.history("bob", None, 1).unwrap()[0].source, "carol");
+        assert!(store.history("nobody", None, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_amounts_beyond_i64() {
+        let mut store = store();
+        let huge = u64::MAX;
+        store
+            .save(&block(1, 3600, &[("alice", "bob", "USDC", huge)]))
+            .unwrap();
+        store
+            .save(&block(2, 3700, &[("alice", "bob", "USDC", huge)]))
+            .unwrap();
+
+        assert_eq!(
+            store.history("bob", None, 10).unwrap()[1].amount,
+            Amount::new(huge, 6)
+        );
+        let expected = Volume::new(2 * huge as u128, 6);
+        assert_eq!(store.top_senders(None, 1).unwrap()[0].volume, expected);
+        assert_eq!(store.volume_by_hour(None, 1).unwrap()[0].volume, expected);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::domain::address_book::{AddressBook, AddressKind};
+use crate::infrastructure::config::parse;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+// A label is either a plain name or a table with the name and what the
+// address is, e.g. `"<address>" = { label = "Desk", kind = "exchange" }`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Label {
+    Name(String),
+    Entry {
+        label: String,
+        #[serde(default)]
+        kind: Option<AddressKind>,
+    },
+}
+
+// Builds the address book from the built-in labels, overridden by the entries
+// of a TOML or JSON file mapping addresses to labels, when one is given.
+pub fn load(path: Option<&Path>) -> Result<AddressBook> {
+    let mut address_book = AddressBook::builtin();
+    let Some(path) = path else {
+        return Ok(address_book);
+    };
+
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("reading labels from {}", path.display()))?;
+    let labels: HashMap<String, Label> = parse(&content, path)?;
+
+    for (address, label) in labels {
+        match label {
+            Label::Name(label) => address_book.insert(address, label, AddressKind::Wallet),
+            Label::Entry { label, kind } => {
+                address_book.insert(address, label, kind.unwrap_or(AddressKind::Wallet))
+            }
+        }
+    }
+
+    Ok(address_book)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_labels() {
+        let toml = r#"
+            "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU" = "Treasury"
+            "5tzFkiKscXHK5ZXCGbXZxdw7gTjjD1mBwuoFbhUvuAi9" = { label = "Binance 2", kind = "exchange" }
+        "#;
+        let json = r#"{ "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU": "Treasury" }"#;
+
+        let labels: HashMap<String, Label> = parse(toml, Path::new("labels.toml")).unwrap();
+        assert_eq!(labels.len(), 2);
+        assert!(matches!(
+            &labels["5tzFkiKscXHK5ZXCGbXZxdw7gTjjD1mBwuoFbhUvuAi9"],
+            Label::Entry {
+                kind: Some(AddressKind::Exchange),
+                ..
+            }
+        ));
+
+        let labels: HashMap<String, Label> = parse(json, Path::new("labels.JSON")).unwrap();
+        assert!(matches!(
+            &labels["7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU"],
+            Label::Name(name) if name == "Treasury"
+        ));
+
+        assert!(parse::<HashMap<String, Label>>("not = [valid", Path::new("labels.toml")).is_err());
+    }
+}
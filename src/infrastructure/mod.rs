@@ -1 +1,5 @@
+pub mod cluster;
+pub mod config;
+pub mod decoders;
 pub mod gateways;
+pub mod labels;
@@ -2,3 +2,5 @@ pub mod application;
 pub mod cli;
 pub mod domain;
 pub mod infrastructure;
+pub mod tui;
+pub mod web;
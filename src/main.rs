@@ -1,28 +1,389 @@
 use clap::Parser;
+use solana_tx_visualizer::application::gateways::checkpoint::Checkpoint;
+use solana_tx_visualizer::application::presenters::alert_presenter::AlertPresenter;
+use solana_tx_visualizer::application::presenters::checkpoint_presenter::CheckpointPresenter;
+use solana_tx_visualizer::application::presenters::csv_presenter::CsvPresenter;
+use solana_tx_visualizer::application::presenters::dot_presenter::DotPresenter;
+use solana_tx_visualizer::application::presenters::html_presenter::HtmlPresenter;
+use solana_tx_visualizer::application::presenters::json_presenter::JsonPresenter;
+use solana_tx_visualizer::application::presenters::sankey_presenter::SankeyPresenter;
+use solana_tx_visualizer::application::presenters::stats_presenter::{StatsOutput, StatsPresenter};
+use solana_tx_visualizer::application::presenters::store_presenter::StorePresenter;
+use solana_tx_visualizer::application::presenters::text_presenter::TextPresenter;
+use solana_tx_visualizer::application::presenters::Presenter;
+use solana_tx_visualizer::application::services::account_service::AccountService;
 use solana_tx_visualizer::application::services::block_service::BlockService;
+use solana_tx_visualizer::application::services::query_service::QueryService;
+use solana_tx_visualizer::application::services::range_service::RangeService;
+use solana_tx_visualizer::application::services::trace_service::TraceService;
 use solana_tx_visualizer::application::services::track_service::TrackService;
-use solana_tx_visualizer::cli::{Cli, Commands};
+use solana_tx_visualizer::application::services::transaction_service::TransactionService;
+use solana_tx_visualizer::cli::{Cli, Commands, Format, GraphArgs, Report, Sink};
+use solana_tx_visualizer::domain::address_book::AddressBook;
+use solana_tx_visualizer::domain::trace::TraceDirection;
+use solana_tx_visualizer::infrastructure::config::{self, AlertsConfig};
 use solana_tx_visualizer::infrastructure::gateways::block_gateway::BlockGatewayImpl;
+use solana_tx_visualizer::infrastructure::gateways::checkpoint_file::FileCheckpoint;
+use solana_tx_visualizer::infrastructure::gateways::sqlite_store::SqliteStore;
+use solana_tx_visualizer::infrastructure::labels;
+use solana_tx_visualizer::web::{self, BroadcastPresenter};
+use std::collections::HashSet;
+use std::time::Duration;
+
+fn presenter(format: Format, graph: &GraphArgs, address_book: &AddressBook) -> Box<dyn Presenter> {
+    let stdout = std::io::stdout();
+    let address_book = address_book.clone();
+
+    match format {
+        Format::Text => Box::new(TextPresenter::new(stdout, address_book)),
+        Format::Json => Box::new(JsonPresenter::new(stdout, address_book)),
+        Format::Jsonl => Box::new(JsonPresenter::new_lines(stdout, address_book)),
+        Format::Csv => Box::new(CsvPresenter::new(stdout, address_book)),
+        Format::Graph => Box::new(DotPresenter::new(stdout, graph.options(), address_book)),
+        Format::Html => Box::new(HtmlPresenter::new(stdout, address_book)),
+        Format::Sankey => Box::new(SankeyPresenter::new(stdout, graph.options(), address_book)),
+    }
+}
+
+// Statistics follow the output format, on stderr when the format has no room
+// for them.
+fn stats_presenter(
+    format: Format,
+    interval: Duration,
+    address_book: &AddressBook,
+) -> Box<dyn Presenter> {
+    let address_book = address_book.clone();
+
+    match format {
+        Format::Text => Box::new(StatsPresenter::new(
+            std::io::stdout(),
+            StatsOutput::Text,
+            interval,
+            address_book,
+        )),
+        Format::Json => Box::new(StatsPresenter::new(
+            std::io::stdout(),
+            StatsOutput::Json,
+            interval,
+            address_book,
+        )),
+        Format::Jsonl => Box::new(StatsPresenter::new(
+            std::io::stdout(),
+            StatsOutput::Jsonl,
+            interval,
+            address_book,
+        )),
+        _ => Box::new(StatsPresenter::new(
+            std::io::stderr(),
+            StatsOutput::Text,
+            interval,
+            address_book,
+        )),
+    }
+}
+
+// Adds the alert engine next to the output when any alert rule is configured.
+fn with_alerts<P: Presenter + 'static>(
+    presenter: P,
+    alerts: &AlertsConfig,
+    address_book: &AddressBook,
+) -> Box<dyn Presenter> {
+    if alerts.rules.is_empty() {
+        return Box::new(presenter);
+    }
+
+    let engine = alerts
+        .engine(address_book.clone())
+        .expect("Error in the alert rules!");
+    let sinks = alerts.sinks().expect("Error setting up the alert sinks!");
+
+    Box::new((presenter, AlertPresenter::new(engine, sinks)))
+}
 
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
+    let address_book = labels::load(cli.labels.as_deref()).expect("Error loading labels!");
+    let config = config::load(cli.config.as_deref()).expect("Error loading the config!");
+    let configured_filter = config.filter.filter().expect("Error in the filter config!");
 
     match cli.command {
-        Commands::Track { cluster } => {
-            let block_gateway = BlockGatewayImpl::new(cluster);
-            let tracker_service = TrackService::new(block_gateway);
+        Commands::Track {
+            connection,
+            mints,
+            format,
+            filter,
+            tui,
+            window,
+            stats,
+            stats_interval,
+            sinks,
+            checkpoint,
+            stall_timeout,
+            commitment,
+        } => {
+            let endpoints = connection.endpoints();
+            let mut block_gateway =
+                BlockGatewayImpl::new(endpoints.rpc_url, endpoints.websocket_url, mints);
+            block_gateway.update_stall_timeout(Duration::from_secs(stall_timeout));
+            block_gateway.update_commitment(commitment);
+            if tui {
+                config
+                    .alerts
+                    .check_dashboard()
+                    .expect("Error in the alert sinks!");
+            }
+            // The dashboard is the output, blocks still go to the sinks and alerts.
+            let mut output: Box<dyn Presenter> = if tui {
+                Box::new(())
+            } else {
+                presenter(format, &GraphArgs::default(), &address_book)
+            };
+            if stats {
+                output = Box::new((
+                    output,
+                    stats_presenter(format, Duration::from_secs(stats_interval), &address_book),
+                ));
+            }
+            for sink in sinks {
+                let store = match sink {
+                    Sink::Sqlite(path) => {
+                        SqliteStore::open(&path).expect("Error opening the sink!")
+                    }
+                };
+                output = Box::new((output, StorePresenter::new(store)));
+            }
+            let mut output = with_alerts(output, &config.alerts, &address_book);
+            let mut start_slot = None;
+            if let Some(path) = checkpoint {
+                let checkpoint = FileCheckpoint::new(path);
+                start_slot = checkpoint
+                    .load()
+                    .expect("Error loading the checkpoint!")
+                    .map(|slot| slot + 1);
+                output = Box::new((output, CheckpointPresenter::new(checkpoint)));
+            }
+            let mut tracker_service = TrackService::new(
+                block_gateway,
+                output,
+                filter.filter(configured_filter),
+                start_slot,
+            );
+
+            if tui {
+                let mut subscription = tracker_service
+                    .subscribe()
+                    .await
+                    .expect("Error subscribing to blocks!");
 
-            tracker_service
-                .track()
+                solana_tx_visualizer::tui::run(
+                    &mut subscription,
+                    Duration::from_secs(window),
+                    address_book,
+                    |block_update| tracker_service.present(block_update),
+                )
                 .await
-                .expect("Error tracking transactions!");
+                .expect("Error running the dashboard!");
+                tracker_service
+                    .finish()
+                    .expect("Error tracking transactions!");
+
+                subscription.unsubscribe().await;
+            } else {
+                tracker_service
+                    .track()
+                    .await
+                    .expect("Error tracking transactions!");
+            }
+        }
+        Commands::Block {
+            connection,
+            mints,
+            format,
+            graph,
+            block_id,
+        } => {
+            let endpoints = connection.endpoints();
+            let block_gateway =
+                BlockGatewayImpl::new(endpoints.rpc_url, endpoints.websocket_url, mints);
+            let mut block_service =
+                BlockService::new(block_gateway, presenter(format, &graph, &address_book));
+
+            block_service
+                .get_by_id(block_id)
+                .expect("Error presenting block!");
+        }
+        Commands::Range {
+            connection,
+            mints,
+            format,
+            graph,
+            from,
+            to,
+            from_time,
+            to_time,
+            concurrency,
+        } => {
+            let endpoints = connection.endpoints();
+            let block_gateway =
+                BlockGatewayImpl::new(endpoints.rpc_url, endpoints.websocket_url, mints);
+            let mut range_service = RangeService::new(
+                block_gateway,
+                presenter(format, &graph, &address_book),
+                concurrency,
+            );
+
+            let from = match (from, from_time) {
+                (Some(from), _) => from,
+                (None, Some(from_time)) => range_service
+                    .resolve_slot(from_time)
+                    .await
+                    .expect("Error resolving the start time!"),
+                (None, None) => unreachable!(),
+            };
+            let to = match (to, to_time) {
+                (Some(to), _) => to,
+                (None, Some(to_time)) => range_service
+                    .resolve_slot(to_time + 1)
+                    .await
+                    .expect("Error resolving the end time!")
+                    .saturating_sub(1),
+                (None, None) => unreachable!(),
+            };
+
+            range_service
+                .range(from, to)
+                .await
+                .expect("Error backfilling transactions!");
+        }
+        Commands::Account {
+            connection,
+            mints,
+            format,
+            graph,
+            address,
+            limit,
+            concurrency,
+        } => {
+            let endpoints = connection.endpoints();
+            let block_gateway =
+                BlockGatewayImpl::new(endpoints.rpc_url, endpoints.websocket_url, mints.clone());
+            let account_service =
+                AccountService::new(block_gateway, mints, concurrency, address_book.clone());
+
+            match format {
+                Format::Text => account_service
+                    .history(&address, limit)
+                    .await
+                    .expect("Error getting account history!"),
+                _ => account_service
+                    .present(
+                        &address,
+                        limit,
+                        &mut presenter(format, &graph, &address_book),
+                    )
+                    .await
+                    .expect("Error presenting account history!"),
+            }
+        }
+        Commands::Trace {
+            connection,
+            mints,
+            format,
+            graph,
+            address,
+            slot,
+            upstream,
+            hops,
+            branches,
+            stops,
+            limit,
+            concurrency,
+        } => {
+            let endpoints = connection.endpoints();
+            let block_gateway =
+                BlockGatewayImpl::new(endpoints.rpc_url, endpoints.websocket_url, mints.clone());
+            // Programs and exchanges from the address book end a branch as well.
+            let mut stops: HashSet<String> = stops.into_iter().collect();
+            stops.extend(address_book.terminals().map(String::from));
+            let trace_service = TraceService::new(
+                block_gateway,
+                mints,
+                concurrency,
+                stops,
+                address_book.clone(),
+            );
+            let direction = if upstream {
+                TraceDirection::Upstream
+            } else {
+                TraceDirection::Downstream
+            };
+
+            let root = trace_service
+                .trace(&address, slot, direction, hops, limit, branches)
+                .await
+                .expect("Error tracing funds!");
+
+            match format {
+                Format::Text => trace_service.print(&root, direction),
+                _ => trace_service
+                    .present(&root, &mut presenter(format, &graph, &address_book))
+                    .expect("Error presenting the trace!"),
+            }
+        }
+        Commands::Serve {
+            connection,
+            mints,
+            filter,
+            bind,
+            stall_timeout,
+            commitment,
+        } => {
+            let endpoints = connection.endpoints();
+            let mut block_gateway =
+                BlockGatewayImpl::new(endpoints.rpc_url, endpoints.websocket_url, mints);
+            block_gateway.update_stall_timeout(Duration::from_secs(stall_timeout));
+            block_gateway.update_commitment(commitment);
+            let presenter = BroadcastPresenter::new(address_book.clone());
+            let sender = presenter.sender();
+            let mut tracker_service = TrackService::new(
+                block_gateway,
+                with_alerts(presenter, &config.alerts, &address_book),
+                filter.filter(configured_filter),
+                None,
+            );
+
+            tokio::try_join!(tracker_service.track(), web::serve(bind, sender))
+                .expect("Error serving transactions!");
+        }
+        Commands::Query {
+            db,
+            report,
+            address,
+            mint,
+            limit,
+        } => {
+            let store = SqliteStore::open(&db).expect("Error opening the database!");
+            let query_service = QueryService::new(store, address_book);
+            let mint = mint.as_ref();
+
+            match report {
+                Report::TopSenders => query_service.top_senders(mint, limit),
+                Report::VolumeByHour => query_service.volume_by_hour(mint, limit),
+                Report::History => query_service.history(&address.unwrap_or_default(), mint, limit),
+            }
+            .expect("Error querying the database!");
         }
-        Commands::Block { cluster, block_id } => {
-            let block_gateway = BlockGatewayImpl::new(cluster);
-            let block_service = BlockService::new(block_gateway);
+        Commands::Tx {
+            connection,
+            signature,
+        } => {
+            let endpoints = connection.endpoints();
+            let block_gateway =
+                BlockGatewayImpl::new(endpoints.rpc_url, endpoints.websocket_url, Vec::new());
+            let transaction_service = TransactionService::new(block_gateway, address_book);
 
-            block_service.get_by_id(block_id);
+            transaction_service.get_by_signature(&signature);
         }
     }
 }
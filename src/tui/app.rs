@@ -0,0 +1,489 @@
+use crate::domain::address_book::AddressBook;
+use crate::domain::amount::Amount;
+use crate::domain::block::Block;
+use crate::domain::commitment::{Commitment, Finality, FinalityUpdate};
+use crate::domain::connection::ConnectionState;
+use crate::domain::stats::{TransferStats, WindowStats, WINDOWS};
+use crate::domain::transaction::Transaction;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+const MAX_FEED_ENTRIES: usize = 1_000;
+const MAX_SLOTS: usize = 120;
+
+#[derive(Debug, Clone)]
+pub struct FeedEntry {
+    pub slot: u64,
+    pub transaction: Transaction,
+    // Not finalized yet, the transfer may still be dropped.
+    pub provisional: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Total {
+    pub address: String,
+    pub amount: Amount,
+}
+
+// State of the dashboard, kept free of any terminal handling so it can be
+// driven by tests with synthetic blocks and key presses.
+pub struct App {
+    pub window: Duration,
+    pub latest_slot: Option<u64>,
+    pub connected: bool,
+    // The last state reported by the subscription while it reconnects.
+    pub connection: Option<ConnectionState>,
+    // Slots given up on, their blocks are missing from the feed.
+    pub missed: usize,
+    pub paused: bool,
+    pub filter: Option<String>,
+    pub input: Option<String>,
+    pub selected: usize,
+    pub mints: Vec<String>,
+    pub focus: usize,
+    pub address_book: AddressBook,
+    stats: TransferStats,
+    feed: VecDeque<FeedEntry>,
+    pending: Vec<Block>,
+    slots: VecDeque<(u64, HashMap<String, u64>)>,
+    flows: VecDeque<(Instant, Transaction)>,
+}
+
+impl App {
+    pub fn new(window: Duration, address_book: AddressBook) -> Self {
+        Self {
+            window,
+            latest_slot: None,
+            connected: true,
+            connection: None,
+            missed: 0,
+            paused: false,
+            filter: None,
+            input: None,
+            selected: 0,
+            mints: Vec::new(),
+            focus: 0,
+            address_book,
+            stats: TransferStats::new(WINDOWS[WINDOWS.len() - 1]),
+            feed: VecDeque::new(),
+            pending: Vec::new(),
+            slots: VecDeque::new(),
+            flows: VecDeque::new(),
+        }
+    }
+
+    // While paused, blocks are buffered and applied in order on resume so the
+    // feed and the selection stay still.
+    pub fn push_block(&mut self, block: Block, now: Instant) {
+        if self.paused {
+            self.pending.push(block);
+        } else {
+            self.apply_block(block, now);
+        }
+    }
+
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn update_connection(&mut self, connection: ConnectionState) {
+        self.connection = Some(connection);
+    }
+
+    // Confirms or retracts the transfers of a block shown before it was
+    // finalized. The statistics keep a dropped block's transfers until they
+    // fall out of their windows.
+    pub fn update_finality(&mut self, update: FinalityUpdate) {
+        match update.finality {
+            Finality::Finalized => {
+                for entry in self
+                    .feed
+                    .iter_mut()
+                    .filter(|entry| entry.slot == update.slot)
+                {
+                    entry.provisional = false;
+                }
+                for block in self
+                    .pending
+                    .iter_mut()
+                    .filter(|block| block.slot == update.slot)
+                {
+                    block.update_commitment(Commitment::Finalized);
+                }
+            }
+            Finality::Dropped => {
+                self.feed.retain(|entry| entry.slot != update.slot);
+                self.pending.retain(|block| block.slot != update.slot);
+                self.selected = self.selected.min(self.visible().len().saturating_sub(1));
+            }
+        }
+    }
+
+    pub fn disconnect(&mut self) {
+        self.connected = false;
+    }
+
+    // Drops transfers that fell out of the rolling window.
+    pub fn expire(&mut self, now: Instant) {
+        while let Some((received_at, _)) = self.flows.front() {
+            if now.duration_since(*received_at) <= self.window {
+                break;
+            }
+            self.flows.pop_front();
+        }
+    }
+
+    pub fn focused_mint(&self) -> Option<&str> {
+        self.mints.get(self.focus).map(String::as_str)
+    }
+
+    // Newest first, restricted to the active filter.
+    pub fn visible(&self) -> Vec<&FeedEntry> {
+        self.feed
+            .iter()
+            .rev()
+            .filter(|entry| self.matches(&entry.transaction))
+            .collect()
+    }
+
+    pub fn selected_entry(&self) -> Option<&FeedEntry> {
+        self.visible().get(self.selected).copied()
+    }
+
+    // Raw volume of the focused mint per slot, oldest first.
+    pub fn volumes(&self) -> Vec<u64> {
+        let Some(mint) = self.focused_mint() else {
+            return Vec::new();
+        };
+
+        self.slots
+            .iter()
+            .map(|(_, volumes)| volumes.get(mint).copied().unwrap_or(0))
+            .collect()
+    }
+
+    // Statistics over the 1m, 5m and 1h windows, for every mint.
+    pub fn window_stats(&self) -> Vec<WindowStats> {
+        self.stats.windows(0)
+    }
+
+    pub fn top_senders(&self, limit: usize) -> Vec<Total> {
+        self.top(limit, |transaction| &transaction.source.address)
+    }
+
+    pub fn top_receivers(&self, limit: usize) -> Vec<Total> {
+        self.top(limit, |transaction| &transaction.destination.address)
+    }
+
+    // Returns false when the user asked to quit.
+    pub fn handle_key(&mut self, key: KeyEvent, now: Instant) -> bool {
+        if let Some(input) = self.input.as_mut() {
+            match key.code {
+                KeyCode::Enter => {
+                    let input = self.input.take().unwrap_or_default();
+                    self.filter = Some(input).filter(|input| !input.is_empty());
+                    self.selected = 0;
+                }
+                KeyCode::Esc => self.input = None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char('p') | KeyCode::Char(' ') => self.toggle_pause(now),
+            KeyCode::Down | KeyCode::Char('j') => {
+                let visible = self.visible().len();
+                if self.selected + 1 < visible {
+                    self.selected += 1;
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Home | KeyCode::Char('g') => self.selected = 0,
+            KeyCode::End | KeyCode::Char('G') => {
+                self.selected = self.visible().len().saturating_sub(1)
+            }
+            KeyCode::Char('/') => self.input = Some(String::new()),
+            KeyCode::Char('c') => {
+                self.filter = None;
+                self.selected = 0;
+            }
+            KeyCode::Char('m') if !self.mints.is_empty() => {
+                self.focus = (self.focus + 1) % self.mints.len()
+            }
+            _ => {}
+        }
+
+        true
+    }
+
+    fn toggle_pause(&mut self, now: Instant) {
+        self.paused = !self.paused;
+
+        if !self.paused {
+            for block in std::mem::take(&mut self.pending) {
+                self.apply_block(block, now);
+            }
+        }
+    }
+
+    fn apply_block(&mut self, block: Block, now: Instant) {
+        self.stats.record(&block, chrono::Utc::now().timestamp());
+        let mut volumes: HashMap<String, u64> = HashMap::new();
+        let provisional = block.commitment != Commitment::Finalized;
+        let added = block
+            .transactions
+            .iter()
+            .filter(|transaction| self.matches(transaction))
+            .count();
+
+        for transaction in block.transactions {
+            let label = transaction.token.label();
+            let volume = volumes.entry(label.clone()).or_default();
+            *volume = volume.saturating_add(transaction.amount().raw);
+
+            if !self.mints.contains(&label) {
+                self.mints.push(label);
+            }
+
+            self.flows.push_back((now, transaction.clone()));
+            self.feed.push_back(FeedEntry {
+                slot: block.slot,
+                transaction,
+                provisional,
+            });
+        }
+
+        // Keep the selected row on the same transaction as new ones arrive on top.
+        if self.selected > 0 {
+            self.selected += added;
+        }
+
+        while self.feed.len() > MAX_FEED_ENTRIES {
+            self.feed.pop_front();
+        }
+        self.selected = self.selected.min(self.visible().len().saturating_sub(1));
+
+        self.slots.push_back((block.slot, volumes));
+        while self.slots.len() > MAX_SLOTS {
+            self.slots.pop_front();
+        }
+
+        self.latest_slot = Some(block.slot);
+        self.expire(now);
+    }
+
+    // Case-insensitive match on the signature, either address or the mint label.
+    fn matches(&self, transaction: &Transaction) -> bool {
+        let Some(filter) = &self.filter else {
+            return true;
+        };
+        let filter = filter.to_lowercase();
+        let source = self.address_book.get(&transaction.source.address);
+        let destination = self.address_book.get(&transaction.destination.address);
+
+        [
+            transaction.signature.as_str(),
+            transaction.source.address.as_str(),
+            transaction.destination.address.as_str(),
+            transaction.token.label().as_str(),
+            source.unwrap_or_default(),
+            destination.unwrap_or_default(),
+        ]
+        .iter()
+        .any(|field| field.to_lowercase().contains(&filter))
+    }
+
+    fn top<F: Fn(&Transaction) -> &String>(&self, limit: usize, address: F) -> Vec<Total> {
+        let Some(mint) = self.focused_mint() else {
+            return Vec::new();
+        };
+        let mut totals: HashMap<&String, Amount> = HashMap::new();
+
+        for (_, transaction) in &self.flows {
+            if transaction.token.label() != mint {
+                continue;
+            }
+
+            let amount = transaction.amount();
+            let total = totals
+                .entry(address(transaction))
+                .or_insert_with(|| Amount::zero(amount.decimals));
+            *total = total
+                .checked_add(amount)
+                .unwrap_or(Amount::new(u64::MAX, amount.decimals));
+        }
+
+        let mut totals: Vec<Total> = totals
+            .into_iter()
+            .map(|(address, amount)| Total {
+                address: address.clone(),
+                amount,
+            })
+            .collect();
+        totals.sort_by(|a, b| {
+            b.amount
+                .raw
+                .cmp(&a.amount.raw)
+                .then_with(|| a.address.cmp(&b.address))
+        });
+        totals.truncate(limit);
+
+        totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::address_book::AddressKind;
+    use crate::domain::mint::Mint;
+    use crate::domain::program::Program;
+
+    fn block(slot: u64, transfers: &[(&str, &str, u64)]) -> Block {
+        let mut block = Block::new(slot, "blockhash".to_string());
+
+        for (index, (source, destination, amount)) in transfers.iter().enumerate() {
+            block.add_transaction(Transaction::new(
+                format!("sig-{}-{}", slot, index),
+                Account::new(*source, 1, Amount::zero(6)),
+                Account::new(*destination, 2, Amount::zero(6)),
+                Program::new("program", 3),
+                "USDC".parse::<Mint>().unwrap(),
+                Amount::new(*amount, 6),
+            ));
+        }
+
+        block
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_push_block_aggregates_volume_and_top_flows() {
+        let now = Instant::now();
+        let mut app = App::new(Duration::from_secs(60), AddressBook::new());
+
+        app.push_block(block(1, &[("alice", "bob", 10), ("carol", "bob", 5)]), now);
+        app.push_block(block(2, &[("alice", "carol", 20)]), now);
+
+        assert_eq!(app.latest_slot, Some(2));
+        assert_eq!(app.volumes(), vec![15, 20]);
+        assert_eq!(app.visible()[0].transaction.signature, "sig-2-0");
+        assert_eq!(
+            app.top_senders(1),
+            vec![Total {
+                address: "alice".to_string(),
+                amount: Amount::new(30, 6),
+            }]
+        );
+        assert_eq!(app.top_receivers(2)[1].address, "bob");
+
+        let stats = app.window_stats();
+        assert_eq!(stats[0].transfers, 3);
+        assert_eq!(stats[0].transfers_per_slot, 1.5);
+
+        app.expire(now + Duration::from_secs(61));
+        assert!(app.top_senders(5).is_empty());
+        assert_eq!(app.volumes(), vec![15, 20]);
+    }
+
+    #[test]
+    fn test_finality_confirms_or_retracts_transfers() {
+        let now = Instant::now();
+        let mut app = App::new(Duration::from_secs(60), AddressBook::new());
+        for slot in [1, 2] {
+            let mut block = block(slot, &[("alice", "bob", 10)]);
+            block.update_commitment(Commitment::Confirmed);
+            app.push_block(block, now);
+        }
+        assert!(app.visible().iter().all(|entry| entry.provisional));
+
+        app.update_finality(FinalityUpdate {
+            slot: 1,
+            blockhash: "hash".to_string(),
+            finality: Finality::Finalized,
+        });
+        app.update_finality(FinalityUpdate {
+            slot: 2,
+            blockhash: "hash".to_string(),
+            finality: Finality::Dropped,
+        });
+
+        assert_eq!(app.visible().len(), 1);
+        assert_eq!(app.visible()[0].slot, 1);
+        assert!(!app.visible()[0].provisional);
+    }
+
+    #[test]
+    fn test_pause_buffers_blocks_until_resumed() {
+        let now = Instant::now();
+        let mut app = App::new(Duration::from_secs(60), AddressBook::new());
+
+        app.handle_key(key(KeyCode::Char('p')), now);
+        app.push_block(block(1, &[("alice", "bob", 10)]), now);
+
+        assert!(app.visible().is_empty());
+        assert_eq!(app.pending(), 1);
+
+        app.handle_key(key(KeyCode::Char(' ')), now);
+
+        assert_eq!(app.visible().len(), 1);
+        assert_eq!(app.pending(), 0);
+    }
+
+    #[test]
+    fn test_filter_and_navigation() {
+        let now = Instant::now();
+        let mut app = App::new(Duration::from_secs(60), AddressBook::new());
+        app.address_book
+            .insert("dave", "Exchange Desk", AddressKind::Exchange);
+        app.push_block(
+            block(
+                1,
+                &[("alice", "bob", 1), ("carol", "dave", 2), ("alice", "x", 3)],
+            ),
+            now,
+        );
+
+        app.handle_key(key(KeyCode::Down), now);
+        app.handle_key(key(KeyCode::Down), now);
+        app.handle_key(key(KeyCode::Down), now);
+        assert_eq!(app.selected, 2);
+
+        for code in [KeyCode::Char('/'), KeyCode::Char('A'), KeyCode::Char('l')] {
+            app.handle_key(key(code), now);
+        }
+        assert_eq!(app.input.as_deref(), Some("Al"));
+        app.handle_key(key(KeyCode::Enter), now);
+
+        assert_eq!(app.filter.as_deref(), Some("Al"));
+        assert_eq!(app.visible().len(), 2);
+        assert_eq!(
+            app.selected_entry().unwrap().transaction.signature,
+            "sig-1-2"
+        );
+
+        app.handle_key(key(KeyCode::Char('c')), now);
+        assert_eq!(app.visible().len(), 3);
+
+        for code in [KeyCode::Char('/'), KeyCode::Char('d'), KeyCode::Char('e')] {
+            app.handle_key(key(code), now);
+        }
+        app.handle_key(key(KeyCode::Enter), now);
+        assert_eq!(app.visible()[0].transaction.signature, "sig-1-1");
+        assert_eq!(app.visible().len(), 1);
+        app.handle_key(key(KeyCode::Char('c')), now);
+        assert!(!app.handle_key(key(KeyCode::Char('q')), now));
+    }
+}
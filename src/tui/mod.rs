@@ -0,0 +1,71 @@
+pub mod app;
+pub mod ui;
+
+use crate::application::gateways::block_gateway::BlockUpdate;
+use crate::application::services::track_service::Subscription;
+use crate::domain::address_book::AddressBook;
+use crate::tui::app::App;
+use anyhow::Result;
+use crossterm::event::{Event, EventStream, KeyEventKind};
+use futures_util::StreamExt;
+use ratatui::DefaultTerminal;
+use std::time::{Duration, Instant};
+
+// Runs the full-screen dashboard until the user quits, always restoring the
+// terminal before returning. Every update is also passed to `on_update`, e.g.
+// for the sinks and alerts.
+pub async fn run<F: FnMut(&BlockUpdate) -> Result<()>>(
+    subscription: &mut Subscription,
+    window: Duration,
+    address_book: AddressBook,
+    on_update: F,
+) -> Result<()> {
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, subscription, window, address_book, on_update).await;
+
+    ratatui::restore();
+
+    result
+}
+
+async fn event_loop<F: FnMut(&BlockUpdate) -> Result<()>>(
+    terminal: &mut DefaultTerminal,
+    subscription: &mut Subscription,
+    window: Duration,
+    address_book: AddressBook,
+    mut on_update: F,
+) -> Result<()> {
+    let mut app = App::new(window, address_book);
+    let mut events = EventStream::new();
+    let mut tick = tokio::time::interval(Duration::from_secs(1));
+
+    loop {
+        terminal.draw(|frame| ui::draw(frame, &app))?;
+
+        tokio::select! {
+            block_update = subscription.recv(), if app.connected => {
+                if let Some(block_update) = &block_update {
+                    on_update(block_update)?;
+                }
+                match block_update {
+                    Some(BlockUpdate::Block(block)) => app.push_block(block, Instant::now()),
+                    Some(BlockUpdate::Missed(_)) => app.missed += 1,
+                    Some(BlockUpdate::Connection(state)) => app.update_connection(state),
+                    Some(BlockUpdate::Finality(update)) => app.update_finality(update),
+                    None => app.disconnect(),
+                }
+            }
+            event = events.next() => match event {
+                Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => {
+                    if !app.handle_key(key, Instant::now()) {
+                        return Ok(());
+                    }
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e.into()),
+                None => return Ok(()),
+            },
+            _ = tick.tick() => app.expire(Instant::now()),
+        }
+    }
+}
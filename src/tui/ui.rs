@@ -0,0 +1,301 @@
+use crate::domain::account::Account;
+use crate::domain::address_book::AddressBook;
+use crate::domain::connection::ConnectionState;
+use crate::domain::mint::shorten;
+use crate::tui::app::{App, Total};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{
+    Block as Panel, Borders, Cell, Paragraph, Row, Sparkline, Table, TableState, Wrap,
+};
+use ratatui::Frame;
+
+const TOP_LIMIT: usize = 10;
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [header, body, detail, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(10),
+        Constraint::Length(9),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [feed, side] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(body);
+    let [volume, stats, senders, receivers] = Layout::vertical([
+        Constraint::Length(7),
+        Constraint::Length(6),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+    ])
+    .areas(side);
+
+    draw_header(frame, app, header);
+    draw_feed(frame, app, feed);
+    draw_volume(frame, app, volume);
+    draw_stats(frame, app, stats);
+    draw_totals(
+        frame,
+        app,
+        senders,
+        "Top senders",
+        app.top_senders(TOP_LIMIT),
+    );
+    draw_totals(
+        frame,
+        app,
+        receivers,
+        "Top receivers",
+        app.top_receivers(TOP_LIMIT),
+    );
+    draw_detail(frame, app, detail);
+    draw_footer(frame, app, footer);
+}
+
+fn draw_header(frame: &mut Frame, app: &App, area: Rect) {
+    let mut spans = vec![Span::styled(
+        match app.latest_slot {
+            Some(slot) => format!(" Latest block: {} ", slot),
+            None => " Waiting for blocks… ".to_string(),
+        },
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+
+    if app.paused {
+        spans.push(Span::styled(
+            format!(" PAUSED ({} blocks buffered) ", app.pending()),
+            Style::default().fg(Color::Black).bg(Color::Yellow),
+        ));
+    }
+    if !app.connected {
+        spans.push(Span::styled(
+            " DISCONNECTED ",
+            Style::default().fg(Color::White).bg(Color::Red),
+        ));
+    } else if let Some(connection) = app
+        .connection
+        .as_ref()
+        .filter(|connection| **connection != ConnectionState::Connected)
+    {
+        spans.push(Span::styled(
+            format!(" {} ", connection),
+            Style::default().fg(Color::Black).bg(Color::Yellow),
+        ));
+    }
+    if app.missed > 0 {
+        spans.push(Span::styled(
+            format!(" {} slots missed ", app.missed),
+            Style::default().fg(Color::White).bg(Color::Red),
+        ));
+    }
+    if let Some(filter) = &app.filter {
+        spans.push(Span::raw(format!(" filter: {:?} ", filter)));
+    }
+
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+fn draw_feed(frame: &mut Frame, app: &App, area: Rect) {
+    let visible = app.visible();
+    let rows = visible.iter().map(|entry| {
+        let transaction = &entry.transaction;
+        Row::new(vec![
+            Cell::from(entry.slot.to_string()),
+            Cell::from(shorten(&transaction.signature)),
+            Cell::from(app.address_book.label(&transaction.source.address)),
+            Cell::from(app.address_book.label(&transaction.destination.address)),
+            Cell::from(Line::from(transaction.amount().to_string()).right_aligned()),
+            Cell::from(transaction.token.label()),
+        ])
+        .style(if entry.provisional {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default()
+        })
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(10),
+            Constraint::Length(11),
+            Constraint::Length(11),
+            Constraint::Length(11),
+            Constraint::Min(12),
+            Constraint::Length(11),
+        ],
+    )
+    .header(
+        Row::new(vec!["Slot", "Signature", "From", "To", "Amount", "Token"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+    .block(
+        Panel::default()
+            .borders(Borders::ALL)
+            .title(format!(" Live feed ({}) ", visible.len())),
+    );
+
+    let mut state = TableState::default().with_selected(Some(app.selected));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_volume(frame: &mut Frame, app: &App, area: Rect) {
+    let volumes = app.volumes();
+    let title = match app.focused_mint() {
+        Some(mint) => format!(" {} volume per slot ", mint),
+        None => " Volume per slot ".to_string(),
+    };
+
+    // Only the most recent slots that fit in the panel are drawn.
+    let width = area.width.saturating_sub(2) as usize;
+    let start = volumes.len().saturating_sub(width);
+
+    let sparkline = Sparkline::default()
+        .block(Panel::default().borders(Borders::ALL).title(title))
+        .data(&volumes[start..])
+        .style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(sparkline, area);
+}
+
+fn draw_stats(frame: &mut Frame, app: &App, area: Rect) {
+    let mint = app.focused_mint();
+    let rows = app.window_stats().into_iter().map(|window| {
+        let stats = window
+            .mints
+            .iter()
+            .find(|stats| Some(stats.token.label().as_str()) == mint);
+        let amount = |amount: Option<String>| {
+            Cell::from(Line::from(amount.unwrap_or_default()).right_aligned())
+        };
+
+        Row::new(vec![
+            Cell::from(format!("{}m", window.window_seconds / 60)),
+            Cell::from(Line::from(window.transfers.to_string()).right_aligned()),
+            Cell::from(Line::from(format!("{:.1}", window.transfers_per_slot)).right_aligned()),
+            Cell::from(
+                Line::from(format!(
+                    "{}/{}",
+                    window.unique_senders, window.unique_receivers
+                ))
+                .right_aligned(),
+            ),
+            amount(stats.map(|stats| stats.volume_per_minute.to_string())),
+            amount(stats.map(|stats| stats.median.to_string())),
+            amount(stats.map(|stats| stats.p99.to_string())),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(3),
+            Constraint::Length(6),
+            Constraint::Length(5),
+            Constraint::Length(9),
+            Constraint::Fill(2),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(
+        Row::new(vec![
+            "",
+            "Count",
+            "/slot",
+            "Snd/Rcv",
+            "Per minute",
+            "Median",
+            "p99",
+        ])
+        .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Panel::default().borders(Borders::ALL).title(match mint {
+        Some(mint) => format!(" {} stats ", mint),
+        None => " Stats ".to_string(),
+    }));
+
+    frame.render_widget(table, area);
+}
+
+fn draw_totals(frame: &mut Frame, app: &App, area: Rect, title: &str, totals: Vec<Total>) {
+    let rows = totals.into_iter().map(|total| {
+        Row::new(vec![
+            Cell::from(app.address_book.label(&total.address)),
+            Cell::from(Line::from(total.amount.to_string()).right_aligned()),
+        ])
+    });
+    let title = format!(" {} ({}s) ", title, app.window.as_secs());
+    let table = Table::new(rows, [Constraint::Length(11), Constraint::Min(12)])
+        .block(Panel::default().borders(Borders::ALL).title(title));
+
+    frame.render_widget(table, area);
+}
+
+fn draw_detail(frame: &mut Frame, app: &App, area: Rect) {
+    let panel = Panel::default().borders(Borders::ALL).title(" Detail ");
+    let Some(entry) = app.selected_entry() else {
+        frame.render_widget(Paragraph::new("No transaction selected").block(panel), area);
+        return;
+    };
+    let transaction = &entry.transaction;
+    let label = transaction.token.label();
+
+    let mut lines = vec![
+        Line::from(format!("Signature: {}", transaction.signature)),
+        Line::from(format!(
+            "Slot: {}    Program: {}",
+            entry.slot, transaction.program.address
+        )),
+        Line::from(format!(
+            "Amount: {} {}{}",
+            transaction.amount(),
+            label,
+            match transaction.fee {
+                Some(fee) => format!("    withheld fee: {} {}", fee, label),
+                None => String::new(),
+            }
+        )),
+        account_line("From", &transaction.source, &label, &app.address_book),
+        account_line("To", &transaction.destination, &label, &app.address_book),
+    ];
+    if transaction.token.address != label {
+        lines.push(Line::from(format!("Mint: {}", transaction.token.address)));
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(panel)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn account_line(
+    name: &str,
+    account: &Account,
+    label: &str,
+    address_book: &AddressBook,
+) -> Line<'static> {
+    let known = match address_book.get(&account.address) {
+        Some(known) => format!(" ({})", known),
+        None => String::new(),
+    };
+
+    Line::from(format!(
+        "{}: {}{}    {} → {} {}",
+        name, account.address, known, account.pre_balance, account.post_balance, label
+    ))
+}
+
+fn draw_footer(frame: &mut Frame, app: &App, area: Rect) {
+    let line = match &app.input {
+        Some(input) => Line::from(format!("/{}▏  Enter apply · Esc cancel", input)),
+        None => {
+            Line::from(" q quit · p pause · ↑/↓ select · / filter · c clear filter · m switch mint")
+                .style(Style::default().fg(Color::DarkGray))
+        }
+    };
+
+    frame.render_widget(Paragraph::new(line), area);
+}
@@ -0,0 +1,160 @@
+use crate::application::presenters::json_presenter::JsonPresenter;
+use crate::application::presenters::Presenter;
+use crate::domain::address_book::AddressBook;
+use crate::domain::block::Block;
+use crate::domain::commitment::FinalityUpdate;
+use crate::domain::connection::ConnectionState;
+use crate::domain::slot_tracker::MissedSlot;
+use anyhow::Result;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use axum::Router;
+use std::net::SocketAddr;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+const INDEX: &str = include_str!("index.html");
+// Blocks kept for slow clients before they start skipping some.
+const CHANNEL_CAPACITY: usize = 256;
+
+// Forwards every block to the connected browsers, serialized the same way as
+// `--format jsonl`.
+pub struct BroadcastPresenter {
+    sender: broadcast::Sender<String>,
+    json: JsonPresenter<Vec<u8>>,
+}
+
+impl BroadcastPresenter {
+    pub fn new(address_book: AddressBook) -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+
+        Self {
+            sender,
+            json: JsonPresenter::new_lines(Vec::new(), address_book),
+        }
+    }
+
+    pub fn sender(&self) -> broadcast::Sender<String> {
+        self.sender.clone()
+    }
+}
+
+impl BroadcastPresenter {
+    fn broadcast(&mut self) -> Result<()> {
+        let message = std::mem::take(self.json.get_mut());
+
+        // Sending only fails when nobody is connected, which is fine.
+        let _ = self
+            .sender
+            .send(String::from_utf8(message)?.trim_end().to_string());
+
+        Ok(())
+    }
+}
+
+impl Presenter for BroadcastPresenter {
+    fn present(&mut self, block: &Block) -> Result<()> {
+        self.json.present(block)?;
+        self.broadcast()
+    }
+
+    fn missed(&mut self, slot: &MissedSlot) -> Result<()> {
+        self.json.missed(slot)?;
+        self.broadcast()
+    }
+
+    fn connection(&mut self, state: &ConnectionState) -> Result<()> {
+        self.json.connection(state)?;
+        self.broadcast()
+    }
+
+    fn finality(&mut self, update: &FinalityUpdate) -> Result<()> {
+        self.json.finality(update)?;
+        self.broadcast()
+    }
+}
+
+// Serves the page at `/` and the block feed at `/ws` until the process exits.
+pub async fn serve(address: SocketAddr, sender: broadcast::Sender<String>) -> Result<()> {
+    let router = Router::new()
+        .route("/", get(index))
+        .route("/ws", get(feed))
+        .with_state(sender);
+    let listener = tokio::net::TcpListener::bind(address).await?;
+
+    println!("Serving on http://{}", listener.local_addr()?);
+
+    Ok(axum::serve(listener, router).await?)
+}
+
+async fn index() -> Html<&'static str> {
+    Html(INDEX)
+}
+
+async fn feed(
+    websocket: WebSocketUpgrade,
+    State(sender): State<broadcast::Sender<String>>,
+) -> impl IntoResponse {
+    websocket.on_upgrade(move |socket| forward(socket, sender.subscribe()))
+}
+
+async fn forward(mut socket: WebSocket, mut receiver: broadcast::Receiver<String>) {
+    loop {
+        tokio::select! {
+            message = receiver.recv() => match message {
+                Ok(message) => {
+                    if socket.send(Message::Text(message)).await.is_err() {
+                        return;
+                    }
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return,
+            },
+            // The page never sends anything, this only notices it going away.
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::amount::Amount;
+    use crate::domain::mint::Mint;
+    use crate::domain::program::Program;
+    use crate::domain::transaction::Transaction;
+
+    #[test]
+    fn test_broadcast_presenter() {
+        let mut presenter = BroadcastPresenter::new(AddressBook::builtin());
+        let mut receiver = presenter.sender().subscribe();
+        let mut block = Block::new(42, "hash".to_string());
+        block.add_transaction(Transaction::new(
+            "sig".to_string(),
+            Account::new("alice", 1, Amount::zero(6)),
+            Account::new("bob", 2, Amount::zero(6)),
+            Program::new("program", 3),
+            "USDC".parse::<Mint>().unwrap(),
+            Amount::new(1_500_000, 6),
+        ));
+
+        presenter.present(&block).unwrap();
+
+        let message = receiver.try_recv().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&message).unwrap();
+
+        assert!(!message.ends_with('\n'));
+        assert_eq!(value["schema_version"], 1);
+        assert_eq!(value["block"]["transactions"][0]["amount"]["ui"], "1.5");
+        assert!(value["labels"].as_object().unwrap().is_empty());
+
+        presenter.present(&block).unwrap();
+        assert_eq!(receiver.try_recv().unwrap(), message);
+        assert!(!INDEX.contains("src=\"http"));
+    }
+}